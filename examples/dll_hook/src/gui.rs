@@ -209,9 +209,14 @@ impl eframe::App for MyApp {
                 Event::KismetMessage {
                     message,
                     verbosity: _,
-                    warning_id: _,
+                    warning_id,
                 } => {
-                    self.kismet_log.push_str(&format!("Kismet VM: {message}\n"));
+                    if warning_id.is_none() {
+                        self.kismet_log.push_str(&format!("Kismet VM: {message}\n"));
+                    } else {
+                        self.kismet_log
+                            .push_str(&format!("Kismet VM [{warning_id}]: {message}\n"));
+                    }
                 }
                 Event::KismetPrintMessage { message } => {
                     self.kismet_log
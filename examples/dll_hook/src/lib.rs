@@ -13,7 +13,7 @@ use patternsleuth::resolvers::impl_try_collector;
 use patternsleuth::resolvers::unreal::blueprint_library::UFunctionBind;
 use patternsleuth::resolvers::unreal::UObjectBaseUtilityGetPathName;
 use patternsleuth::resolvers::unreal::{
-    fname::FNameToString,
+    fname::{FNamePool, FNameToString},
     game_loop::{FEngineLoopInit, UGameEngineTick},
     gmalloc::GMalloc,
     guobject_array::{
@@ -126,6 +126,8 @@ impl_try_collector! {
         gmalloc: GMalloc,
         guobject_array: GUObjectArray,
         fnametostring: FNameToString,
+        #[optional]
+        fname_pool: FNamePool,
         allocate_uobject: FUObjectArrayAllocateUObjectIndex,
         free_uobject: FUObjectArrayFreeUObjectIndex,
         game_tick: UGameEngineTick,
@@ -140,6 +142,16 @@ impl_try_collector! {
     }
 }
 
+/// Reinterpret a resolved address as the function pointer type declared for it, e.g.
+/// `resolved_fn!(resolution.fframe_step as ue::FnFFrameStep)`. This is the only place resolved
+/// addresses should be transmuted so the signature is always spelled out at the use site
+#[macro_export]
+macro_rules! resolved_fn {
+    ($($path:ident).+ as $ty:ty) => {
+        unsafe { std::mem::transmute::<usize, $ty>($($path).+.0) }
+    };
+}
+
 static mut GLOBALS: Option<Globals> = None;
 
 pub struct Globals {
@@ -153,16 +165,25 @@ impl Globals {
         unsafe { &**(self.resolution.gmalloc.0 as *const *const ue::FMalloc) }
     }
     pub fn fframe_step(&self) -> ue::FnFFrameStep {
-        unsafe { std::mem::transmute(self.resolution.fframe_step.0) }
+        resolved_fn!(self.resolution.fframe_step as ue::FnFFrameStep)
     }
     pub fn fframe_step_explicit_property(&self) -> ue::FnFFrameStepExplicitProperty {
-        unsafe { std::mem::transmute(self.resolution.fframe_step_explicit_property.0) }
+        resolved_fn!(
+            self.resolution.fframe_step_explicit_property as ue::FnFFrameStepExplicitProperty
+        )
     }
     pub fn fname_to_string(&self) -> ue::FnFNameToString {
-        unsafe { std::mem::transmute(self.resolution.fnametostring.0) }
+        resolved_fn!(self.resolution.fnametostring as ue::FnFNameToString)
+    }
+    pub fn fname_pool(&self) -> Option<&ue::FNamePool> {
+        let pool = self.resolution.fname_pool.as_ref()?;
+        Some(unsafe { &*(pool.0 as *const ue::FNamePool) })
     }
     pub fn uobject_base_utility_get_path_name(&self) -> ue::FnUObjectBaseUtilityGetPathName {
-        unsafe { std::mem::transmute(self.resolution.uobject_base_utility_get_path_name.0) }
+        resolved_fn!(
+            self.resolution.uobject_base_utility_get_path_name
+                as ue::FnUObjectBaseUtilityGetPathName
+        )
     }
     pub fn guobject_array(&self) -> parking_lot::FairMutexGuard<'static, &ue::FUObjectArray> {
         self.guobject_array.lock()
@@ -196,6 +217,9 @@ fn dump_backtrace() {
 
 unsafe fn patch(bin_dir: PathBuf) -> Result<()> {
     let exe = patternsleuth::process::internal::read_image()?;
+    for range in exe.unreadable_ranges() {
+        info!("skipping unreadable range {range:x?}");
+    }
 
     info!("starting scan");
     let resolution = exe.resolve(DllHookResolution::resolver())?;
@@ -22,6 +22,17 @@ pub type FnFFrameStepExplicitProperty = unsafe extern "system" fn(
 pub type FnFNameToString = unsafe extern "system" fn(&FName, &mut FString);
 impl Display for FName {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // reading the name pool directly does not call into the engine so is safe from any
+        // thread, engines without one (pre 4.23) fall back to FName::ToString
+        if let Some(name) = globals()
+            .fname_pool()
+            .and_then(|pool| pool.get(self.comparison_index))
+        {
+            return match self.number {
+                0 => write!(f, "{name}"),
+                number => write!(f, "{name}_{}", number - 1),
+            };
+        }
         let mut string = FString::new();
         unsafe {
             (globals().fname_to_string())(self, &mut string);
@@ -177,6 +188,19 @@ impl FUObjectArray {
     pub fn objects(&self) -> CriticalSectionGuard<'_, '_, FChunkedFixedUObjectArray> {
         CriticalSectionGuard::lock(&self.obj_objects_critical, &self.obj_objects)
     }
+    /// Find the class default object of the class named `class_name` by scanning the object
+    /// array for an object flagged `RF_ClassDefaultObject` whose class has that name
+    pub fn find_cdo(&self, class_name: &str) -> Option<*const UObjectBase> {
+        self.objects()
+            .iter()
+            .flatten()
+            .find(|obj| {
+                obj.object_flags
+                    .contains(EObjectFlags::RF_ClassDefaultObject)
+                    && obj.class().name() == class_name
+            })
+            .map(|obj| obj as *const UObjectBase)
+    }
     pub fn allocate_serial_number(&self, index: ObjectIndex) -> i32 {
         use std::sync::atomic::Ordering;
 
@@ -372,6 +396,46 @@ bitflags::bitflags! {
         const FUNC_AllFlags = 0xffffffff;
     }
 }
+bitflags::bitflags! {
+    #[derive(Debug, Clone)]
+    pub struct EClassCastFlags: u64 {
+        const CASTCLASS_None = 0x0000000000000000;
+        const CASTCLASS_UField = 0x0000000000000001;
+        const CASTCLASS_FInt8Property = 0x0000000000000002;
+        const CASTCLASS_UEnum = 0x0000000000000004;
+        const CASTCLASS_UStruct = 0x0000000000000008;
+        const CASTCLASS_UScriptStruct = 0x0000000000000010;
+        const CASTCLASS_UClass = 0x0000000000000020;
+        const CASTCLASS_FByteProperty = 0x0000000000000040;
+        const CASTCLASS_FIntProperty = 0x0000000000000080;
+        const CASTCLASS_FFloatProperty = 0x0000000000000100;
+        const CASTCLASS_FUInt64Property = 0x0000000000000200;
+        const CASTCLASS_FClassProperty = 0x0000000000000400;
+        const CASTCLASS_FUInt32Property = 0x0000000000000800;
+        const CASTCLASS_FInterfaceProperty = 0x0000000000001000;
+        const CASTCLASS_FNameProperty = 0x0000000000002000;
+        const CASTCLASS_FStrProperty = 0x0000000000004000;
+        const CASTCLASS_FProperty = 0x0000000000008000;
+        const CASTCLASS_FObjectProperty = 0x0000000000010000;
+        const CASTCLASS_FBoolProperty = 0x0000000000020000;
+        const CASTCLASS_FUInt16Property = 0x0000000000040000;
+        const CASTCLASS_UFunction = 0x0000000000080000;
+        const CASTCLASS_FStructProperty = 0x0000000000100000;
+        const CASTCLASS_FArrayProperty = 0x0000000000200000;
+        const CASTCLASS_FInt64Property = 0x0000000000400000;
+        const CASTCLASS_FDelegateProperty = 0x0000000000800000;
+        const CASTCLASS_FNumericProperty = 0x0000000001000000;
+        const CASTCLASS_FMulticastDelegateProperty = 0x0000000002000000;
+        const CASTCLASS_FObjectPropertyBase = 0x0000000004000000;
+        const CASTCLASS_FWeakObjectProperty = 0x0000000008000000;
+        const CASTCLASS_FLazyObjectProperty = 0x0000000010000000;
+        const CASTCLASS_FSoftObjectProperty = 0x0000000020000000;
+        const CASTCLASS_FTextProperty = 0x0000000040000000;
+        const CASTCLASS_FInt16Property = 0x0000000080000000;
+        const CASTCLASS_FDoubleProperty = 0x0000000100000000;
+        const CASTCLASS_FSoftClassProperty = 0x0000000200000000;
+    }
+}
 
 #[derive(Debug)]
 #[repr(C)]
@@ -421,8 +485,10 @@ pub struct FStructBaseChain {
 #[derive(Debug)]
 #[repr(C)]
 struct FFieldClass {
-    // TODO
     name: FName,
+    id: u64,
+    cast_flags: EClassCastFlags,
+    // TODO remaining members
 }
 
 #[derive(Debug)]
@@ -435,15 +501,40 @@ struct FFieldVariant {
 #[derive(Debug)]
 #[repr(C)]
 pub struct FField {
+    vtable: *const c_void,
     class_private: *const FFieldClass,
     owner: FFieldVariant,
-    next: *const FField,
-    name_private: FName,
+    pub next: *const FField,
+    pub name_private: FName,
     flags_private: EObjectFlags,
 }
 
+impl FField {
+    /// Whether the class of this field derives from `FProperty`
+    pub fn is_property(&self) -> bool {
+        unsafe { self.class_private.as_ref() }.is_some_and(|class| {
+            class
+                .cast_flags
+                .contains(EClassCastFlags::CASTCLASS_FProperty)
+        })
+    }
+}
+
+#[derive(Debug)]
+#[repr(C)]
 pub struct FProperty {
-    // TODO
+    pub ffield: FField,
+    pub array_dim: i32,
+    pub element_size: i32,
+    pub property_flags: u64,
+    pub rep_index: u16,
+    pub blueprint_replication_condition: u8,
+    pub offset_internal: i32,
+    pub rep_notify_func: FName,
+    pub property_link_next: *const FProperty,
+    pub next_ref: *const FProperty,
+    pub destructor_link_next: *const FProperty,
+    pub post_construct_link_next: *const FProperty,
 }
 
 #[derive(Debug)]
@@ -466,6 +557,74 @@ pub struct UStruct {
     pub unversioned_schema: *const (),           //TODO const FUnversionedStructSchema*
 }
 
+impl UStruct {
+    /// Properties declared directly on this struct, excluding those inherited from
+    /// `super_struct`
+    pub fn child_properties(&self) -> impl Iterator<Item = &FProperty> {
+        let mut next = self.child_properties;
+        std::iter::from_fn(move || unsafe {
+            // non-property fields can appear here in principle so skip them
+            loop {
+                let field = next.as_ref()?;
+                next = field.next;
+                if field.is_property() {
+                    return Some(&*(field as *const FField as *const FProperty));
+                }
+            }
+        })
+    }
+    /// Find property `name` on this struct or any of its super structs
+    pub fn find_property(&self, name: &str) -> Option<&FProperty> {
+        let mut next: *const UStruct = self;
+        while let Some(ustruct) = unsafe { next.as_ref() } {
+            if let Some(property) = ustruct
+                .child_properties()
+                .find(|p| p.ffield.name_private.to_string() == name)
+            {
+                return Some(property);
+            }
+            next = ustruct.super_struct;
+        }
+        None
+    }
+    /// Offset of property `name` from the start of an instance of this struct
+    pub fn property_offset(&self, name: &str) -> Option<usize> {
+        self.find_property(name).map(|p| p.offset_internal as usize)
+    }
+}
+
+impl UClass {
+    pub fn name(&self) -> String {
+        self.ustruct
+            .ufield
+            .uobject
+            .uobject_base_utility
+            .uobject_base
+            .name_private
+            .to_string()
+    }
+}
+
+impl UObjectBase {
+    pub fn class(&self) -> &UClass {
+        unsafe { &*self.class_private }
+    }
+    /// Offset of property `name` within this object, looked up by walking the reflection data
+    /// of the object's class so offsets need not be hardcoded per build
+    pub fn property_offset(&self, name: &str) -> Option<usize> {
+        self.class().ustruct.property_offset(name)
+    }
+    /// Pointer to property `name` of the object at `this`. Takes a raw pointer rather than
+    /// `&self` so that the property may be written through the returned pointer
+    ///
+    /// # Safety
+    /// `this` must point to a live object and `T` must match the type of the property
+    pub unsafe fn property_ptr<T>(this: *mut Self, name: &str) -> Option<*mut T> {
+        let offset = (*this).property_offset(name)?;
+        Some(this.cast::<u8>().add(offset).cast())
+    }
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct UFunction {
@@ -494,6 +653,12 @@ pub struct FName {
     pub comparison_index: FNameEntryId,
     pub number: u32,
 }
+impl FName {
+    /// Whether this is `NAME_None`
+    pub fn is_none(&self) -> bool {
+        self.comparison_index.value == 0 && self.number == 0
+    }
+}
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -501,6 +666,102 @@ pub struct FNameEntryId {
     pub value: u32,
 }
 
+const FNAME_BLOCK_OFFSET_BITS: u32 = 16;
+const FNAME_BLOCK_OFFSETS: usize = 1 << FNAME_BLOCK_OFFSET_BITS;
+const FNAME_ENTRY_STRIDE: usize = 2;
+const FNAME_MAX_BLOCKS: usize = 8192;
+
+#[derive(Debug)]
+#[repr(C)]
+pub struct FNameEntryAllocator {
+    lock: *const c_void,
+    current_block: u32,
+    current_byte_cursor: u32,
+    blocks: [*const u8; FNAME_MAX_BLOCKS],
+}
+
+/// UE 4.23+ name pool. Entries are packed into blocks at a fixed stride and addressed by
+/// `(block << 16) | (byte offset / stride)`
+#[derive(Debug)]
+#[repr(C)]
+pub struct FNamePool {
+    pub entries: FNameEntryAllocator,
+}
+impl FNamePool {
+    pub fn iter(&self) -> NamePoolIterator<'_> {
+        NamePoolIterator {
+            pool: self,
+            block: 0,
+            cursor: 0,
+        }
+    }
+    /// Look up the name of entry `id` without calling into the engine, e.g. from threads where
+    /// `FName::ToString` is unsafe to call. Does not include the `_N` number suffix
+    pub fn get(&self, id: FNameEntryId) -> Option<String> {
+        let block = id.value >> FNAME_BLOCK_OFFSET_BITS;
+        let cursor = (id.value as usize & (FNAME_BLOCK_OFFSETS - 1)) * FNAME_ENTRY_STRIDE;
+        unsafe { self.decode(block, cursor) }.map(|(name, _)| name)
+    }
+    /// Size in bytes of the used portion of `block`
+    fn block_size(&self, block: u32) -> usize {
+        if block == self.entries.current_block {
+            self.entries.current_byte_cursor as usize
+        } else {
+            FNAME_BLOCK_OFFSETS * FNAME_ENTRY_STRIDE
+        }
+    }
+    /// Decode the entry at `cursor` bytes into `block`, returning the name and the entry size
+    /// in bytes or `None` if there is no entry there
+    unsafe fn decode(&self, block: u32, cursor: usize) -> Option<(String, usize)> {
+        let data = *self.entries.blocks.get(block as usize)?;
+        if data.is_null() || cursor + 2 > self.block_size(block) {
+            return None;
+        }
+        let entry = data.add(cursor);
+        let header = (entry as *const u16).read_unaligned();
+        let is_wide = header & 1 != 0;
+        let len = (header >> 6) as usize;
+        if len == 0 {
+            return None;
+        }
+        let chars = entry.add(2);
+        let (name, bytes) = if is_wide {
+            let slice = std::slice::from_raw_parts(chars as *const u16, len);
+            (String::from_utf16_lossy(slice), len * 2)
+        } else {
+            let slice = std::slice::from_raw_parts(chars, len);
+            (slice.iter().map(|&c| c as char).collect(), len)
+        };
+        let size = (2 + bytes).next_multiple_of(FNAME_ENTRY_STRIDE);
+        Some((name, size))
+    }
+}
+
+/// Walks every allocated entry of an [`FNamePool`] yielding `(FNameEntryId, name)`
+pub struct NamePoolIterator<'a> {
+    pool: &'a FNamePool,
+    block: u32,
+    cursor: usize,
+}
+impl Iterator for NamePoolIterator<'_> {
+    type Item = (FNameEntryId, String);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.block <= self.pool.entries.current_block {
+            if let Some((name, size)) = unsafe { self.pool.decode(self.block, self.cursor) } {
+                let id = FNameEntryId {
+                    value: (self.block << FNAME_BLOCK_OFFSET_BITS)
+                        | (self.cursor / FNAME_ENTRY_STRIDE) as u32,
+                };
+                self.cursor += size;
+                return Some((id, name));
+            }
+            self.block += 1;
+            self.cursor = 0;
+        }
+        None
+    }
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct TSharedPtr<T> {
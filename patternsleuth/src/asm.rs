@@ -0,0 +1,533 @@
+//! Assemble patterns from a small subset of Intel syntax x86-64 assembly.
+//!
+//! Instructions are separated by `;` or newlines. Any immediate, displacement, or branch target
+//! written as `?` is encoded as a 32-bit value and wildcarded in the resulting pattern. `?8`
+//! selects the 8-bit encoding instead. `jmp`/`jcc` accept a `short` keyword for rel8 targets.
+//! RIP-relative memory operands and branch targets must be wildcarded.
+//!
+//! ```text
+//! push rbx; sub rsp, ?8; mov rax, [rip+?]; xor eax, eax; call ?; cmp qword ptr [rcx+?8], 0
+//! ```
+//!
+//! Supported mnemonics: `push`, `pop`, `mov`, `lea`, `add`, `or`, `and`, `sub`, `xor`, `cmp`,
+//! `test`, `call`, `jmp`, `je`/`jz`, `jne`/`jnz`, `ret`, `int3`
+//!
+//! Where x86 has multiple encodings for the same instruction the MSVC preferred form is used,
+//! e.g. `mov rbx, rcx` assembles to `48 8B D9`.
+
+use anyhow::{bail, Context, Result};
+use iced_x86::{Code, Encoder, Instruction, MemoryOperand, Register};
+use patternsleuth_scanner::{Pattern, PatternSimple};
+
+const PLACEHOLDER32: i64 = 0x12345678;
+const PLACEHOLDER8: i64 = 0x12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Known(i64),
+    Wild8,
+    Wild32,
+}
+impl Value {
+    fn is_wild(self) -> bool {
+        !matches!(self, Value::Known(_))
+    }
+    fn fits_i8(self) -> bool {
+        match self {
+            Value::Known(v) => i8::try_from(v).is_ok(),
+            Value::Wild8 => true,
+            Value::Wild32 => false,
+        }
+    }
+    fn value(self) -> i64 {
+        match self {
+            Value::Known(v) => v,
+            Value::Wild8 => PLACEHOLDER8,
+            Value::Wild32 => PLACEHOLDER32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Mem {
+    size: Option<usize>,
+    base: Register,
+    index: Register,
+    scale: u32,
+    displacement: Option<Value>,
+}
+impl Mem {
+    fn operand(&self) -> MemoryOperand {
+        let (displ, displ_size) = match self.displacement {
+            None => (0, 0),
+            Some(Value::Wild8) => (PLACEHOLDER8, 1),
+            Some(v) => (v.value(), if v.fits_i8() { 1 } else { 4 }),
+        };
+        MemoryOperand::with_base_index_scale_displ_size(
+            self.base, self.index, self.scale, displ, displ_size,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Operand {
+    Reg(Register),
+    Imm(Value),
+    Mem(Mem),
+}
+
+fn parse_register(s: &str) -> Option<Register> {
+    use Register::*;
+    #[rustfmt::skip]
+    const REGISTERS: &[(&str, Register)] = &[
+        ("rax", RAX), ("rcx", RCX), ("rdx", RDX), ("rbx", RBX),
+        ("rsp", RSP), ("rbp", RBP), ("rsi", RSI), ("rdi", RDI),
+        ("r8", R8), ("r9", R9), ("r10", R10), ("r11", R11),
+        ("r12", R12), ("r13", R13), ("r14", R14), ("r15", R15),
+        ("eax", EAX), ("ecx", ECX), ("edx", EDX), ("ebx", EBX),
+        ("esp", ESP), ("ebp", EBP), ("esi", ESI), ("edi", EDI),
+        ("r8d", R8D), ("r9d", R9D), ("r10d", R10D), ("r11d", R11D),
+        ("r12d", R12D), ("r13d", R13D), ("r14d", R14D), ("r15d", R15D),
+        ("al", AL), ("cl", CL), ("dl", DL), ("bl", BL),
+        ("spl", SPL), ("bpl", BPL), ("sil", SIL), ("dil", DIL),
+        ("r8b", R8L), ("r9b", R9L), ("r10b", R10L), ("r11b", R11L),
+        ("r12b", R12L), ("r13b", R13L), ("r14b", R14L), ("r15b", R15L),
+        ("rip", RIP),
+    ];
+    REGISTERS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map(|(_, reg)| *reg)
+}
+
+fn parse_value(s: &str) -> Result<Value> {
+    Ok(match s {
+        "?" => Value::Wild32,
+        "?8" => Value::Wild8,
+        _ => {
+            let (neg, s) = s.strip_prefix('-').map(|s| (true, s)).unwrap_or((false, s));
+            let v = s
+                .strip_prefix("0x")
+                .map(|s| i64::from_str_radix(s, 16))
+                .unwrap_or_else(|| s.parse())
+                .with_context(|| format!("bad value \"{s}\""))?;
+            Value::Known(if neg { -v } else { v })
+        }
+    })
+}
+
+fn parse_memory(size: Option<usize>, inner: &str) -> Result<Mem> {
+    let mut mem = Mem {
+        size,
+        base: Register::None,
+        index: Register::None,
+        scale: 1,
+        displacement: None,
+    };
+
+    // split into signed terms
+    let mut terms = vec![];
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        if (c == '+' || c == '-') && i != 0 {
+            terms.push(&inner[start..i]);
+            start = i;
+        }
+    }
+    terms.push(&inner[start..]);
+
+    for term in terms {
+        let term = term.trim();
+        let (neg, term) = if let Some(t) = term.strip_prefix('-') {
+            (true, t.trim())
+        } else {
+            (false, term.strip_prefix('+').unwrap_or(term).trim())
+        };
+        if let Some((reg, scale)) = term.split_once('*') {
+            let reg = parse_register(reg.trim()).context("bad index register")?;
+            mem.index = reg;
+            mem.scale = scale.trim().parse().context("bad scale")?;
+        } else if let Some(reg) = parse_register(term) {
+            if neg {
+                bail!("register cannot be negated in memory operand");
+            }
+            if mem.base == Register::None {
+                mem.base = reg;
+            } else {
+                mem.index = reg;
+            }
+        } else {
+            let value = match parse_value(term)? {
+                Value::Known(v) if neg => Value::Known(-v),
+                v => v,
+            };
+            if mem.displacement.is_some() {
+                bail!("multiple displacements in memory operand");
+            }
+            mem.displacement = Some(value);
+        }
+    }
+    if mem.base == Register::RIP && !mem.displacement.is_some_and(Value::is_wild) {
+        bail!("RIP relative displacement must be wildcarded");
+    }
+    Ok(mem)
+}
+
+fn parse_operand(s: &str) -> Result<Operand> {
+    let s = s.trim();
+    if let Some(start) = s.find('[') {
+        let size = match s[..start].trim().to_ascii_lowercase().as_str() {
+            "" => None,
+            "byte ptr" => Some(1),
+            "word ptr" => Some(2),
+            "dword ptr" => Some(4),
+            "qword ptr" => Some(8),
+            other => bail!("unknown operand size \"{other}\""),
+        };
+        let inner = s[start + 1..]
+            .strip_suffix(']')
+            .context("unclosed memory operand")?;
+        Ok(Operand::Mem(parse_memory(size, inner)?))
+    } else if let Some(reg) = parse_register(s) {
+        Ok(Operand::Reg(reg))
+    } else {
+        Ok(Operand::Imm(parse_value(s)?))
+    }
+}
+
+struct Forms {
+    r_rm: Code,
+    rm_r: Code,
+    rm_imm8: Option<Code>,
+    rm_imm32: Option<Code>,
+}
+
+#[rustfmt::skip]
+fn forms(mnemonic: &str, size: usize) -> Option<Forms> {
+    use Code::*;
+    let f = |r_rm, rm_r, rm_imm8, rm_imm32| Forms { r_rm, rm_r, rm_imm8, rm_imm32 };
+    Some(match (mnemonic, size) {
+        ("mov", 8) => f(Mov_r64_rm64, Mov_rm64_r64, None, Some(Mov_rm64_imm32)),
+        ("mov", 4) => f(Mov_r32_rm32, Mov_rm32_r32, None, Some(Mov_rm32_imm32)),
+        ("mov", 1) => f(Mov_r8_rm8, Mov_rm8_r8, Some(Mov_rm8_imm8), None),
+        ("test", 8) => f(Test_rm64_r64, Test_rm64_r64, None, Some(Test_rm64_imm32)),
+        ("test", 4) => f(Test_rm32_r32, Test_rm32_r32, None, Some(Test_rm32_imm32)),
+        ("test", 1) => f(Test_rm8_r8, Test_rm8_r8, Some(Test_rm8_imm8), None),
+        ("add", 8) => f(Add_r64_rm64, Add_rm64_r64, Some(Add_rm64_imm8), Some(Add_rm64_imm32)),
+        ("add", 4) => f(Add_r32_rm32, Add_rm32_r32, Some(Add_rm32_imm8), Some(Add_rm32_imm32)),
+        ("add", 1) => f(Add_r8_rm8, Add_rm8_r8, Some(Add_rm8_imm8), None),
+        ("or", 8) => f(Or_r64_rm64, Or_rm64_r64, Some(Or_rm64_imm8), Some(Or_rm64_imm32)),
+        ("or", 4) => f(Or_r32_rm32, Or_rm32_r32, Some(Or_rm32_imm8), Some(Or_rm32_imm32)),
+        ("or", 1) => f(Or_r8_rm8, Or_rm8_r8, Some(Or_rm8_imm8), None),
+        ("and", 8) => f(And_r64_rm64, And_rm64_r64, Some(And_rm64_imm8), Some(And_rm64_imm32)),
+        ("and", 4) => f(And_r32_rm32, And_rm32_r32, Some(And_rm32_imm8), Some(And_rm32_imm32)),
+        ("and", 1) => f(And_r8_rm8, And_rm8_r8, Some(And_rm8_imm8), None),
+        ("sub", 8) => f(Sub_r64_rm64, Sub_rm64_r64, Some(Sub_rm64_imm8), Some(Sub_rm64_imm32)),
+        ("sub", 4) => f(Sub_r32_rm32, Sub_rm32_r32, Some(Sub_rm32_imm8), Some(Sub_rm32_imm32)),
+        ("sub", 1) => f(Sub_r8_rm8, Sub_rm8_r8, Some(Sub_rm8_imm8), None),
+        ("xor", 8) => f(Xor_r64_rm64, Xor_rm64_r64, Some(Xor_rm64_imm8), Some(Xor_rm64_imm32)),
+        ("xor", 4) => f(Xor_r32_rm32, Xor_rm32_r32, Some(Xor_rm32_imm8), Some(Xor_rm32_imm32)),
+        ("xor", 1) => f(Xor_r8_rm8, Xor_rm8_r8, Some(Xor_rm8_imm8), None),
+        ("cmp", 8) => f(Cmp_r64_rm64, Cmp_rm64_r64, Some(Cmp_rm64_imm8), Some(Cmp_rm64_imm32)),
+        ("cmp", 4) => f(Cmp_r32_rm32, Cmp_rm32_r32, Some(Cmp_rm32_imm8), Some(Cmp_rm32_imm32)),
+        ("cmp", 1) => f(Cmp_r8_rm8, Cmp_rm8_r8, Some(Cmp_rm8_imm8), None),
+        _ => return None,
+    })
+}
+
+/// Encoded instruction along with which value was wildcarded
+enum Wild {
+    None,
+    Immediate,
+    Displacement,
+    Both,
+    /// Trailing branch displacement of the given size
+    Branch(usize),
+}
+
+fn build_instruction(line: &str) -> Result<(Instruction, Wild)> {
+    let line = line.trim();
+    let (mnemonic, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let mnemonic = mnemonic.to_ascii_lowercase();
+    let rest = rest.trim();
+
+    // branches
+    let branch = match mnemonic.as_str() {
+        "jmp" => Some((Code::Jmp_rel8_64, Code::Jmp_rel32_64)),
+        "je" | "jz" => Some((Code::Je_rel8_64, Code::Je_rel32_64)),
+        "jne" | "jnz" => Some((Code::Jne_rel8_64, Code::Jne_rel32_64)),
+        "call" => Some((Code::Call_rel32_64, Code::Call_rel32_64)),
+        _ => None,
+    };
+    if let Some((short, near)) = branch {
+        let (code, size, target) = if let Some(target) = rest.strip_prefix("short") {
+            if short == near {
+                bail!("{mnemonic} has no short form");
+            }
+            (short, 1, target.trim())
+        } else {
+            (near, 4, rest)
+        };
+        if target == "?" {
+            // placeholder must be out of rel8 range from the encoding address
+            return Ok((
+                Instruction::with_branch(code, if size == 1 { 0x10 } else { 0x12345678 })?,
+                Wild::Branch(size),
+            ));
+        }
+        // indirect call/jmp through register or memory
+        let indirect = match mnemonic.as_str() {
+            "call" => Code::Call_rm64,
+            "jmp" => Code::Jmp_rm64,
+            _ => bail!("branch target must be wildcarded"),
+        };
+        return Ok(match parse_operand(target)? {
+            Operand::Reg(r) => (Instruction::with1(indirect, r)?, Wild::None),
+            Operand::Mem(m) => (
+                Instruction::with1(indirect, m.operand())?,
+                wild(None, m.displacement),
+            ),
+            Operand::Imm(_) => bail!("branch target must be wildcarded"),
+        });
+    }
+
+    let operands = if rest.is_empty() {
+        vec![]
+    } else {
+        rest.split(',')
+            .map(parse_operand)
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    use Operand::*;
+    Ok(match (mnemonic.as_str(), operands.as_slice()) {
+        ("ret", []) => (Instruction::with(Code::Retnq), Wild::None),
+        ("int3", []) => (Instruction::with(Code::Int3), Wild::None),
+        ("push", [Reg(r)]) => (Instruction::with1(Code::Push_r64, *r)?, Wild::None),
+        ("pop", [Reg(r)]) => (Instruction::with1(Code::Pop_r64, *r)?, Wild::None),
+        ("lea", [Reg(r), Mem(m)]) => {
+            let code = if r.is_gpr64() {
+                Code::Lea_r64_m
+            } else {
+                Code::Lea_r32_m
+            };
+            (
+                Instruction::with2(code, *r, m.operand())?,
+                wild(None, m.displacement),
+            )
+        }
+        // B8+r forms
+        ("mov", [Reg(r), Imm(v)]) if r.is_gpr32() => (
+            Instruction::with2(Code::Mov_r32_imm32, *r, v.value() as i32)?,
+            wild(Some(*v), None),
+        ),
+        ("mov", [Reg(r), Imm(Value::Known(v))]) if r.is_gpr64() && i32::try_from(*v).is_err() => {
+            (Instruction::with2(Code::Mov_r64_imm64, *r, *v)?, Wild::None)
+        }
+        (mn, ops) => {
+            let size = ops
+                .iter()
+                .find_map(|op| match op {
+                    Reg(r) => Some(r.size()),
+                    Mem(m) => m.size,
+                    Imm(_) => None,
+                })
+                .with_context(|| format!("could not determine operand size of \"{line}\""))?;
+            let forms =
+                forms(mn, size).with_context(|| format!("unsupported instruction \"{line}\""))?;
+            let imm_code = |v: &Value| {
+                if v.fits_i8() {
+                    forms.rm_imm8.or(forms.rm_imm32)
+                } else {
+                    forms.rm_imm32
+                }
+                .with_context(|| format!("no immediate form for \"{line}\""))
+            };
+            match ops {
+                [Reg(a), Reg(b)] => (Instruction::with2(forms.r_rm, *a, *b)?, Wild::None),
+                [Reg(a), Mem(m)] => (
+                    Instruction::with2(forms.r_rm, *a, m.operand())?,
+                    wild(None, m.displacement),
+                ),
+                [Mem(m), Reg(b)] => (
+                    Instruction::with2(forms.rm_r, m.operand(), *b)?,
+                    wild(None, m.displacement),
+                ),
+                [Reg(a), Imm(v)] => (
+                    Instruction::with2(imm_code(v)?, *a, v.value() as i32)?,
+                    wild(Some(*v), None),
+                ),
+                [Mem(m), Imm(v)] => (
+                    Instruction::with2(imm_code(v)?, m.operand(), v.value() as i32)?,
+                    wild(Some(*v), m.displacement),
+                ),
+                _ => bail!("unsupported operands in \"{line}\""),
+            }
+        }
+    })
+}
+
+fn wild(immediate: Option<Value>, displacement: Option<Value>) -> Wild {
+    match (
+        immediate.is_some_and(Value::is_wild),
+        displacement.is_some_and(Value::is_wild),
+    ) {
+        (false, false) => Wild::None,
+        (true, false) => Wild::Immediate,
+        (false, true) => Wild::Displacement,
+        (true, true) => Wild::Both,
+    }
+}
+
+/// Assemble `source` into a [`Pattern`] with all `?` operands wildcarded
+pub fn assemble_pattern(source: &str) -> Result<Pattern> {
+    let mut sig = vec![];
+    let mut mask = vec![];
+
+    let mut encoder = Encoder::new(64);
+    for line in source
+        .split(|c| c == ';' || c == '\n')
+        .filter(|l| !l.trim().is_empty())
+    {
+        let (instruction, wild) = build_instruction(line)
+            .with_context(|| format!("failed to parse \"{}\"", line.trim()))?;
+
+        let len = encoder
+            .encode(&instruction, 0)
+            .with_context(|| format!("failed to encode \"{}\"", line.trim()))?;
+        let offsets = encoder.get_constant_offsets();
+        let bytes = encoder.take_buffer();
+
+        let mut wildcard = vec![false; len];
+        let mut set = |offset: usize, size: usize| {
+            wildcard[offset..offset + size].fill(true);
+        };
+        match wild {
+            Wild::None => {}
+            Wild::Immediate => set(offsets.immediate_offset(), offsets.immediate_size()),
+            Wild::Displacement => set(offsets.displacement_offset(), offsets.displacement_size()),
+            Wild::Both => {
+                set(offsets.immediate_offset(), offsets.immediate_size());
+                set(offsets.displacement_offset(), offsets.displacement_size());
+            }
+            Wild::Branch(size) => set(len - size, size),
+        }
+
+        for (byte, wild) in bytes.into_iter().zip(wildcard) {
+            sig.push(if wild { 0 } else { byte });
+            mask.push(if wild { 0 } else { 0xff });
+        }
+    }
+
+    if sig.is_empty() {
+        bail!("pattern must match at least one byte");
+    }
+
+    Ok(Pattern {
+        simple: PatternSimple { sig, mask },
+        custom_offset: 0,
+        captures: vec![],
+        xrefs: vec![],
+        label: None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[track_caller]
+    fn check(source: &str, expected: &str) {
+        let pattern = assemble_pattern(source).unwrap();
+        assert_eq!(expected, pattern.to_string(), "assembling \"{source}\"");
+    }
+
+    #[track_caller]
+    fn check_err(source: &str, message: &str) {
+        let err = format!("{:#}", assemble_pattern(source).unwrap_err());
+        assert!(err.contains(message), "\"{source}\" failed with \"{err}\"");
+    }
+
+    #[test]
+    fn test_assemble_register_forms() {
+        check("mov rbx, rcx", "48 8B D9");
+        check("MOV RBX, RCX", "48 8B D9");
+        check("mov r8d, ecx", "44 8B C1");
+        check("xor eax, eax", "33 C0");
+        check("test al, al", "84 C0");
+        check("push rbx", "53");
+        check("push r12", "41 54");
+        check("pop rdi", "5F");
+        check("ret", "C3");
+        check("int3", "CC");
+        check("call rax", "FF D0");
+    }
+
+    #[test]
+    fn test_assemble_immediates() {
+        check("sub rsp, 0x28", "48 83 EC 28");
+        check("sub rsp, 0x128", "48 81 EC 28 01 00 00");
+        check("mov ecx, 5", "B9 05 00 00 00");
+        check("mov rax, 5", "48 C7 C0 05 00 00 00");
+        check("mov rax, 0x123456789", "48 B8 89 67 45 23 01 00 00 00");
+        check("and byte ptr [rbx], -2", "80 23 FE");
+    }
+
+    #[test]
+    fn test_assemble_memory_forms() {
+        check("mov rax, [rcx+0x10]", "48 8B 41 10");
+        check("mov rax, [rcx+0x100]", "48 8B 81 00 01 00 00");
+        check("mov rax, [rcx+rdx*8]", "48 8B 04 D1");
+        check("mov [rsp+0x10], rbx", "48 89 5C 24 10");
+        check("lea rdx, [rsp+0x40]", "48 8D 54 24 40");
+        check("call qword ptr [rax+0x10]", "FF 50 10");
+    }
+
+    #[test]
+    fn test_assemble_wildcards() {
+        check("sub rsp, ?8", "48 83 EC ??");
+        check("mov eax, ?", "B8 ?? ?? ?? ??");
+        check("mov rax, ?", "48 C7 C0 ?? ?? ?? ??");
+        check("mov rax, [rcx+?]", "48 8B 81 ?? ?? ?? ??");
+        check("cmp qword ptr [rcx+?8], 0", "48 83 79 ?? 00");
+        check("mov dword ptr [rcx+?], ?", "C7 81 ?? ?? ?? ?? ?? ?? ?? ??");
+        check("lea rcx, [rip+?]", "48 8D 0D ?? ?? ?? ??");
+        check("call ?", "E8 ?? ?? ?? ??");
+        check("je ?", "0F 84 ?? ?? ?? ??");
+        check("jmp short ?", "EB ??");
+        check("jne short ?", "75 ??");
+    }
+
+    #[test]
+    fn test_assemble_sequence() {
+        check(
+            "mov rax, [rip+?]; call ?",
+            "48 8B 05 ?? ?? ?? ?? E8 ?? ?? ?? ??",
+        );
+        check(
+            "push rbx\nsub rsp, ?8;; xor eax, eax\n",
+            "53 48 83 EC ?? 33 C0",
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        check_err("", "pattern must match at least one byte");
+        check_err(" ; \n", "pattern must match at least one byte");
+        check_err(
+            "mov rax, [rip+0x10]",
+            "RIP relative displacement must be wildcarded",
+        );
+        check_err("jmp 0x10", "branch target must be wildcarded");
+        check_err("je rax", "branch target must be wildcarded");
+        check_err("call short ?", "call has no short form");
+        check_err("imul rax, rcx", "unsupported instruction");
+        check_err("nop", "could not determine operand size");
+        check_err("mov rax, [rcx", "unclosed memory operand");
+        check_err("mov rax, xmmword ptr [rcx]", "unknown operand size");
+        check_err("mov eax, foo", "bad value");
+        check_err("mov rax, [rcx+1+2]", "multiple displacements");
+        check_err("mov rax, [rcx-rdx]", "register cannot be negated");
+        check_err("mov byte ptr [rcx], 0x100", "no immediate form");
+    }
+}
@@ -240,6 +240,10 @@ impl ElfImage {
             #[cfg(feature = "symbols")]
             symbols,
             imports: HashMap::default(),
+            exports: super::read_exports(&object, base_address),
+            build_id: super::read_build_id(&object),
+            global_xrefs: Default::default(),
+            skipped: Default::default(),
             image_type: ImageType::ElfImage(ElfImage {
                 functions: Some(functions),
             }),
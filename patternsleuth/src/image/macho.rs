@@ -0,0 +1,386 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::{Memory, MemoryAccessError, RuntimeFunction};
+
+use super::{Image, ImageType};
+use anyhow::{bail, Context, Result};
+use object::{
+    read::macho::{FatArch, MachOFatFile32, MachOFatFile64},
+    Object, ObjectSection, ObjectSegment, ObjectSymbol, SectionKind, SymbolKind,
+};
+
+const MH_MAGIC_64: u32 = 0xfeedfacf;
+const LC_FUNCTION_STARTS: u32 = 0x26;
+
+pub struct MachOImage {
+    /// Function ranges derived from `LC_FUNCTION_STARTS` and the symbol table. Mach-O does not
+    /// store function ends so each function is assumed to extend until the next one begins.
+    pub functions: Vec<Range<usize>>,
+}
+
+impl MachOImage {
+    pub fn get_function(
+        &self,
+        image: &Image<'_>,
+        address: usize,
+    ) -> Result<Option<RuntimeFunction>, MemoryAccessError> {
+        self.get_root_function(image, address)
+    }
+    pub fn get_root_function(
+        &self,
+        image: &Image<'_>,
+        address: usize,
+    ) -> Result<Option<RuntimeFunction>, MemoryAccessError> {
+        self.get_root_function_range(image, address).map(|range| {
+            range.map(|r| RuntimeFunction {
+                range: r,
+                unwind: 0,
+            })
+        })
+    }
+    pub fn get_root_function_range(
+        &self,
+        _image: &Image<'_>,
+        address: usize,
+    ) -> Result<Option<Range<usize>>, MemoryAccessError> {
+        let i = self.functions.partition_point(|f| f.end <= address);
+        Ok(self
+            .functions
+            .get(i)
+            .filter(|f| f.contains(&address))
+            .cloned())
+    }
+    pub fn get_child_functions(
+        &self,
+        image: &Image<'_>,
+        address: usize,
+    ) -> Result<Vec<RuntimeFunction>, MemoryAccessError> {
+        Ok(self.get_function(image, address)?.into_iter().collect())
+    }
+    pub fn get_root_functions(
+        &self,
+        _image: &Image<'_>,
+    ) -> Result<Vec<Range<usize>>, MemoryAccessError> {
+        Ok(self.functions.clone())
+    }
+}
+
+/// Select the slice of a fat (universal) binary to load, preferring x86_64
+pub fn select_fat_slice(data: &[u8]) -> Result<&[u8]> {
+    fn select<'data, A: FatArch>(arches: &[A], data: &'data [u8]) -> Result<&'data [u8]> {
+        let arch = arches
+            .iter()
+            .find(|a| a.cputype() == object::macho::CPU_TYPE_X86_64)
+            .or_else(|| arches.first())
+            .context("fat binary contains no slices")?;
+        Ok(arch.data(data)?)
+    }
+    match object::FileKind::parse(data)? {
+        object::FileKind::MachOFat32 => select(MachOFatFile32::parse(data)?.arches(), data),
+        object::FileKind::MachOFat64 => select(MachOFatFile64::parse(data)?.arches(), data),
+        _ => Ok(data),
+    }
+}
+
+/// Decode function start addresses from `LC_FUNCTION_STARTS` if present
+fn read_function_starts(data: &[u8], text_vmaddr: usize) -> Result<Vec<usize>> {
+    let u32_at = |offset: usize| -> Result<u32> {
+        Ok(u32::from_le_bytes(
+            data.get(offset..offset + 4)
+                .context("truncated load commands")?
+                .try_into()
+                .unwrap(),
+        ))
+    };
+
+    if u32_at(0)? != MH_MAGIC_64 {
+        bail!("not a 64-bit little endian Mach-O");
+    }
+    let ncmds = u32_at(16)?;
+
+    let mut starts = vec![];
+    // mach_header_64 is 32 bytes
+    let mut offset = 32;
+    for _ in 0..ncmds {
+        let cmd = u32_at(offset)?;
+        let cmdsize = u32_at(offset + 4)? as usize;
+        if cmd == LC_FUNCTION_STARTS {
+            let dataoff = u32_at(offset + 8)? as usize;
+            let datasize = u32_at(offset + 12)? as usize;
+            let uleb = data
+                .get(dataoff..dataoff + datasize)
+                .context("LC_FUNCTION_STARTS out of bounds")?;
+
+            let mut address = text_vmaddr;
+            let mut value = 0;
+            let mut shift = 0;
+            for byte in uleb {
+                if shift >= usize::BITS {
+                    bail!("LC_FUNCTION_STARTS delta is too long");
+                }
+                value |= ((byte & 0x7f) as usize) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    // list is terminated by a zero delta
+                    if value == 0 {
+                        break;
+                    }
+                    address = address
+                        .checked_add(value)
+                        .context("LC_FUNCTION_STARTS address overflows")?;
+                    starts.push(address);
+                    value = 0;
+                    shift = 0;
+                }
+            }
+        }
+        if cmdsize == 0 {
+            bail!("malformed load command");
+        }
+        offset += cmdsize;
+    }
+    Ok(starts)
+}
+
+impl MachOImage {
+    /// Read and parse Mach-O object. `data` must be the thin (single architecture) slice
+    pub fn read_inner<'data, P: AsRef<std::path::Path>>(
+        base_addr: Option<usize>,
+        data: &'data [u8],
+        #[allow(unused_variables)] exe_path: Option<P>,
+        _cache_functions: bool,
+        object: object::File<'data>,
+    ) -> Result<Image<'data>, anyhow::Error> {
+        let base_address = base_addr.unwrap_or(object.relative_address_base() as usize);
+        let memory = Memory::new(&object)?;
+
+        let text_sections = object
+            .sections()
+            .filter(|s| s.kind() == SectionKind::Text)
+            .map(|s| s.address() as usize..(s.address() + s.size()) as usize)
+            .collect::<Vec<_>>();
+
+        let text_symbols = object
+            .symbols()
+            .filter(|s| s.kind() == SymbolKind::Text && s.is_definition() && s.address() != 0)
+            .collect::<Vec<_>>();
+
+        // function start deltas are relative to the start of __TEXT. object reports a relative
+        // address base of 0 for Mach-O so it cannot be used here
+        let text_vmaddr = object
+            .segments()
+            .find(|s| s.name().ok().flatten() == Some("__TEXT"))
+            .map(|s| s.address() as usize)
+            .unwrap_or_default();
+        let mut starts = read_function_starts(data, text_vmaddr).unwrap_or_default();
+        starts.extend(text_symbols.iter().map(|s| s.address() as usize));
+        starts.sort();
+        starts.dedup();
+
+        let functions = starts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &start)| {
+                let section = text_sections.iter().find(|s| s.contains(&start))?;
+                let end = starts
+                    .get(i + 1)
+                    .copied()
+                    .unwrap_or(section.end)
+                    .min(section.end);
+                Some(start..end)
+            })
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "symbols")]
+        let symbols = Some(
+            text_symbols
+                .iter()
+                .filter_map(|s| {
+                    Some((
+                        s.address() as usize,
+                        crate::symbols::Symbol {
+                            name: s.name().ok().filter(|n| !n.is_empty())?.to_string(),
+                        },
+                    ))
+                })
+                .collect(),
+        );
+
+        Ok(Image {
+            base_address,
+            memory,
+            #[cfg(feature = "symbols")]
+            symbols,
+            imports: HashMap::default(),
+            exports: super::read_exports(&object, base_address),
+            build_id: super::read_build_id(&object),
+            global_xrefs: Default::default(),
+            skipped: Default::default(),
+            image_type: ImageType::MachOImage(MachOImage { functions }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEXT_VMADDR: u64 = 0x100000000;
+
+    fn put(data: &mut Vec<u8>, bytes: &[u8]) {
+        data.extend_from_slice(bytes);
+    }
+    fn name16(name: &str) -> [u8; 16] {
+        let mut buf = [0; 16];
+        buf[..name.len()].copy_from_slice(name.as_bytes());
+        buf
+    }
+
+    /// Build a minimal x86_64 Mach-O executable with a single `__TEXT,__text` section at file
+    /// offset 0x400, one `_foo` symbol at `__text + 0x80` and `LC_FUNCTION_STARTS` containing
+    /// `function_starts` verbatim
+    fn build_macho(function_starts: &[u8]) -> Vec<u8> {
+        let text = 0x400u32;
+        let text_size = 0x100u32;
+        let starts_off = text + text_size;
+        let syms_off = starts_off + (function_starts.len() as u32).next_multiple_of(8);
+        let strings = b"\0_foo\0";
+        let strings_off = syms_off + 16;
+
+        let mut data = vec![];
+        // mach_header_64
+        for v in [MH_MAGIC_64, 0x0100_0007, 3, 2, 3, 152 + 24 + 16, 0, 0] {
+            put(&mut data, &v.to_le_bytes());
+        }
+        // LC_SEGMENT_64 __TEXT with one section
+        put(&mut data, &0x19u32.to_le_bytes());
+        put(&mut data, &152u32.to_le_bytes());
+        put(&mut data, &name16("__TEXT"));
+        for v in [TEXT_VMADDR, 0x1000, 0, (text + text_size) as u64] {
+            put(&mut data, &v.to_le_bytes());
+        }
+        for v in [5u32, 5, 1, 0] {
+            put(&mut data, &v.to_le_bytes());
+        }
+        put(&mut data, &name16("__text"));
+        put(&mut data, &name16("__TEXT"));
+        put(&mut data, &(TEXT_VMADDR + text as u64).to_le_bytes());
+        put(&mut data, &(text_size as u64).to_le_bytes());
+        for v in [text, 4, 0, 0, 0x8000_0400, 0, 0, 0] {
+            put(&mut data, &v.to_le_bytes());
+        }
+        // LC_SYMTAB
+        for v in [2u32, 24, syms_off, 1, strings_off, strings.len() as u32] {
+            put(&mut data, &v.to_le_bytes());
+        }
+        // LC_FUNCTION_STARTS
+        for v in [
+            LC_FUNCTION_STARTS,
+            16,
+            starts_off,
+            function_starts.len() as u32,
+        ] {
+            put(&mut data, &v.to_le_bytes());
+        }
+
+        data.resize(text as usize, 0);
+        data.resize((text + text_size) as usize, 0xcc);
+        put(&mut data, function_starts);
+        data.resize(syms_off as usize, 0);
+        // nlist_64 _foo: N_SECT | N_EXT in section 1
+        put(&mut data, &1u32.to_le_bytes());
+        put(&mut data, &[0x0f, 1, 0, 0]);
+        put(&mut data, &(TEXT_VMADDR + text as u64 + 0x80).to_le_bytes());
+        put(&mut data, strings);
+        data
+    }
+
+    fn functions(image: &Image<'_>) -> Vec<Range<usize>> {
+        match &image.image_type {
+            ImageType::MachOImage(macho) => macho.functions.clone(),
+            #[allow(unreachable_patterns)]
+            _ => panic!("expected Mach-O image"),
+        }
+    }
+
+    #[test]
+    fn test_function_starts_and_symbols() {
+        // deltas 0x400, 0x40 from the start of __TEXT and the terminating zero
+        let data = build_macho(&[0x80, 0x08, 0x40, 0x00]);
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+
+        let text = TEXT_VMADDR as usize + 0x400;
+        assert_eq!(
+            vec![
+                text..text + 0x40,
+                text + 0x40..text + 0x80,
+                text + 0x80..text + 0x100
+            ],
+            functions(&image)
+        );
+        assert_eq!(
+            Some(text + 0x40..text + 0x80),
+            image.get_root_function_range(text + 0x50).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_select_fat_slice() {
+        let thin = build_macho(&[0x80, 0x08, 0x00]);
+        let arm = b"not an x86_64 slice";
+
+        let mut fat = vec![];
+        for v in [0xcafebabeu32, 2] {
+            put(&mut fat, &v.to_be_bytes());
+        }
+        // arm64 slice first so the x86_64 one must be picked explicitly
+        for v in [0x0100_000c, 0, 0x1000, arm.len() as u32, 12] {
+            put(&mut fat, &v.to_be_bytes());
+        }
+        for v in [0x0100_0007, 3, 0x2000, thin.len() as u32, 12] {
+            put(&mut fat, &v.to_be_bytes());
+        }
+        fat.resize(0x1000, 0);
+        put(&mut fat, arm);
+        fat.resize(0x2000, 0);
+        put(&mut fat, &thin);
+
+        assert_eq!(thin.as_slice(), select_fat_slice(&fat).unwrap());
+        assert_eq!(thin.as_slice(), select_fat_slice(&thin).unwrap());
+
+        let image = Image::read::<&str>(None, &fat, None, false).unwrap();
+        let text = TEXT_VMADDR as usize + 0x400;
+        assert_eq!(
+            vec![text..text + 0x80, text + 0x80..text + 0x100],
+            functions(&image)
+        );
+    }
+
+    #[test]
+    fn test_malformed_function_starts() {
+        let text_vmaddr = TEXT_VMADDR as usize;
+
+        // zero sized load command would loop forever
+        let mut data = build_macho(&[0x00]);
+        data[32 + 4..32 + 8].copy_from_slice(&0u32.to_le_bytes());
+        let err = read_function_starts(&data, text_vmaddr).unwrap_err();
+        assert_eq!("malformed load command", err.to_string());
+
+        // more continuation bytes than fit in a usize
+        let mut uleb = vec![0x80; 11];
+        uleb.extend([0x01, 0x00]);
+        let err = read_function_starts(&build_macho(&uleb), text_vmaddr).unwrap_err();
+        assert_eq!("LC_FUNCTION_STARTS delta is too long", err.to_string());
+
+        // delta of usize::MAX overflows the address
+        let mut uleb = vec![0xff; 9];
+        uleb.extend([0x01, 0x00]);
+        let err = read_function_starts(&build_macho(&uleb), text_vmaddr).unwrap_err();
+        assert_eq!("LC_FUNCTION_STARTS address overflows", err.to_string());
+
+        // still readable from symbols alone
+        let image = Image::read::<&str>(None, &build_macho(&uleb), None, false).unwrap();
+        let text = text_vmaddr + 0x400;
+        assert_eq!(vec![text + 0x80..text + 0x100], functions(&image));
+    }
+}
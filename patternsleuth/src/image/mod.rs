@@ -1,5 +1,7 @@
 #[cfg(feature = "image-elf")]
 pub mod elf;
+#[cfg(feature = "image-macho")]
+pub mod macho;
 mod macros;
 #[cfg(feature = "image-pe")]
 pub mod pe;
@@ -8,18 +10,21 @@ use crate::*;
 use anyhow::Error;
 #[cfg(feature = "image-elf")]
 use elf::ElfImage;
+#[cfg(feature = "image-macho")]
+use macho::MachOImage;
 #[cfg(feature = "image-pe")]
 use pe::PEImage;
 
 use macros::*;
 
-#[cfg(not(any(feature = "image-pe", feature = "image-elf")))]
-compile_error!("requires at least one of image-pe or image-elf features");
+#[cfg(not(any(feature = "image-pe", feature = "image-elf", feature = "image-macho")))]
+compile_error!("requires at least one of image-pe, image-elf or image-macho features");
 
 image_type_dispatch! {
     @enum ImageType as _image_type_reflection {
         PEImage(PEImage, "image-pe"),
         ElfImage(ElfImage, "image-elf"),
+        MachOImage(MachOImage, "image-macho"),
     }
 
     @fns {
@@ -33,13 +38,99 @@ image_type_dispatch! {
 
 pub use _image_type_reflection as image_type_reflection;
 
+/// Stable identifier of a particular build of an executable
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BuildId {
+    /// PDB signature from the CodeView record of the PE debug directory
+    Pdb { guid: [u8; 16], age: u32 },
+    /// Contents of the ELF `NT_GNU_BUILD_ID` note
+    Gnu(Vec<u8>),
+    /// Mach-O `LC_UUID`
+    Uuid([u8; 16]),
+    /// SHA-256 of executable section contents for images without an embedded identifier
+    TextSha256([u8; 32]),
+}
+impl std::fmt::Display for BuildId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let hex = |f: &mut std::fmt::Formatter<'_>, bytes: &[u8]| {
+            bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+        };
+        match self {
+            Self::Pdb { guid, age } => {
+                hex(f, guid)?;
+                write!(f, "{age:x}")
+            }
+            Self::Gnu(id) => hex(f, id),
+            Self::Uuid(id) => hex(f, id),
+            Self::TextSha256(hash) => hex(f, hash),
+        }
+    }
+}
+
+/// SHA-256 of executable sections with absolute addresses normalized, see
+/// [`Image::text_fingerprint`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextFingerprint(pub [u8; 32]);
+impl std::fmt::Display for TextFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+impl std::str::FromStr for TextFingerprint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.len() != 64 || !s.is_ascii() {
+            anyhow::bail!("expected 64 hex digits, found {s:?}");
+        }
+        let mut hash = [0; 32];
+        for (i, b) in hash.iter_mut().enumerate() {
+            *b = u8::from_str_radix(&s[i * 2..i * 2 + 2], 16)?;
+        }
+        Ok(Self(hash))
+    }
+}
+
+/// Entry of an image's export table
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct ExportEntry {
+    /// Exported name, `None` for exports only reachable by ordinal
+    pub name: Option<String>,
+    /// PE export ordinal, `None` for formats without ordinals
+    pub ordinal: Option<u32>,
+    /// Address of the export, `None` if it is forwarded
+    pub address: Option<usize>,
+    /// Forwarder string in PE form, i.e. `LIBRARY.Name` or `LIBRARY.#ordinal`
+    pub forwarder: Option<String>,
+}
+
+/// Executable container format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Pe,
+    Elf,
+    MachO,
+}
+
 pub struct Image<'data> {
     pub base_address: usize,
     pub memory: Memory<'data>,
     #[cfg(feature = "symbols")]
     pub symbols: Option<HashMap<usize, symbols::Symbol>>,
     pub imports: HashMap<String, HashMap<String, usize>>,
+    pub exports: HashMap<String, usize>,
+    pub(crate) build_id: Option<BuildId>,
     pub image_type: ImageType,
+    /// Lazily computed result of [`Image::global_xrefs`]
+    pub(crate) global_xrefs: std::sync::OnceLock<Vec<(usize, usize)>>,
+    /// Address ranges which could not be read when the image was captured from another process
+    /// and are zero filled, or which were not readable when the image of the current process
+    /// was read, see [`Image::unreadable_ranges`]
+    pub(crate) skipped: Vec<Range<usize>>,
 }
 
 // Type-independent
@@ -50,6 +141,8 @@ impl<'data> Image<'data> {
         exe_path: Option<P>,
         cache_functions: bool,
     ) -> Result<Image<'data>> {
+        #[cfg(feature = "image-macho")]
+        let data = macho::select_fat_slice(data)?;
         let object = object::File::parse(data)?;
         match object {
             #[cfg(feature = "image-elf")]
@@ -60,9 +153,44 @@ impl<'data> Image<'data> {
             object::File::Pe64(_) => {
                 PEImage::read_inner(base_addr, exe_path, cache_functions, object)
             }
+            #[cfg(feature = "image-macho")]
+            object::File::MachO64(_) => {
+                MachOImage::read_inner(base_addr, data, exe_path, cache_functions, object)
+            }
             _ => Err(Error::msg("Unsupported file format")),
         }
     }
+    /// Construct an image from a raw buffer (e.g. a memory snapshot or crafted test data)
+    /// located at `base`. The buffer's headers must match the declared `format`. PE and ELF
+    /// buffers are expected in their mapped layout as read from memory: PE section contents are
+    /// located at their RVA and placed at `base + RVA`, ELF `PT_LOAD` segments are located at
+    /// their offset from the lowest segment and placed at `base + p_vaddr`. Mach-O buffers are
+    /// read in their file layout with sections at their unslid addresses
+    pub fn from_bytes_with_base(
+        data: &'data [u8],
+        base: usize,
+        format: ImageFormat,
+    ) -> Result<Image<'data>> {
+        let kind = object::FileKind::parse(data)?;
+        let matches = match format {
+            ImageFormat::Pe => kind == object::FileKind::Pe64,
+            ImageFormat::Elf => kind == object::FileKind::Elf64,
+            ImageFormat::MachO => matches!(
+                kind,
+                object::FileKind::MachO64
+                    | object::FileKind::MachOFat32
+                    | object::FileKind::MachOFat64
+            ),
+        };
+        if !matches {
+            anyhow::bail!("expected {format:?} image but buffer contains {kind:?}");
+        }
+        match format {
+            #[cfg(feature = "image-pe")]
+            ImageFormat::Pe => PEImage::read_mapped(data, base),
+            _ => Image::read::<&str>(Some(base), data, None, false),
+        }
+    }
     pub fn builder() -> ImageBuilder {
         Default::default()
     }
@@ -73,6 +201,46 @@ impl<'data> Image<'data> {
         resolvers::resolve(self, resolver)
     }
 
+    /// Resolve while recording every resolver's scan candidates, see
+    /// [`resolvers::resolve_candidates`]
+    pub fn resolve_candidates<T: Send + Sync>(
+        &self,
+        resolver: &'static resolvers::ResolverFactory<T>,
+    ) -> (resolvers::Result<T>, HashMap<&'static str, Vec<usize>>) {
+        resolvers::resolve_candidates(self, resolver)
+    }
+
+    /// Resolve with scans limited to `num_threads` threads, see [`resolvers::resolve_threaded`]
+    pub fn resolve_threaded<T: Send + Sync>(
+        &self,
+        resolver: &'static resolvers::ResolverFactory<T>,
+        num_threads: usize,
+    ) -> resolvers::Result<T> {
+        resolvers::resolve_threaded(self, resolver, num_threads)
+    }
+
+    /// Resolve using `pre_resolved` addresses from a previous run where available, see
+    /// [`resolvers::PreResolved`]
+    pub fn resolve_with<T: Send + Sync>(
+        &self,
+        resolver: &'static resolvers::ResolverFactory<T>,
+        pre_resolved: resolvers::PreResolved,
+    ) -> resolvers::Result<T> {
+        resolvers::resolve_with(self, resolver, pre_resolved)
+    }
+
+    /// Run async code against this image without an async runtime, see [`resolvers::eval`]
+    pub fn eval<F, T: Send + Sync>(&self, f: F) -> T
+    where
+        F: for<'ctx> FnOnce(
+                &'ctx resolvers::AsyncContext<'_>,
+            ) -> futures::future::BoxFuture<'ctx, T>
+            + Send
+            + Sync,
+    {
+        resolvers::eval(self, f)
+    }
+
     pub fn resolve_many(
         &self,
         resolvers: &[fn() -> &'static resolvers::DynResolverFactory],
@@ -80,6 +248,22 @@ impl<'data> Image<'data> {
         resolvers::resolve_many(self, resolvers)
     }
 
+    /// Names of every registered singleton resolver which resolves to `address`. Runs all of
+    /// them so is as slow as resolving everything, intended for labeling unknown addresses
+    pub fn identify(&self, address: usize) -> Vec<&'static str> {
+        let named = resolvers::resolvers()
+            .filter(|r| r.singleton)
+            .collect::<Vec<_>>();
+        let getters = named.iter().map(|r| r.getter).collect::<Vec<_>>();
+        named
+            .iter()
+            .zip(self.resolve_many(&getters))
+            .filter_map(|(r, res)| {
+                (res.ok().and_then(|res| res.get()) == Some(address)).then_some(r.name)
+            })
+            .collect()
+    }
+
     pub fn scan<'patterns, S>(
         &self,
         pattern_configs: &'patterns [PatternConfig<S>],
@@ -150,6 +334,375 @@ impl<'data> Image<'data> {
 
         Ok(ScanResult { results })
     }
+
+    /// Address of the exported symbol `name`
+    pub fn export(&self, name: &str) -> Option<usize> {
+        self.exports.get(name).copied()
+    }
+
+    /// Every export of the image including ordinals and forwarders where the format has them,
+    /// suitable for generating proxy DLL stubs
+    pub fn export_manifest(&self) -> Vec<ExportEntry> {
+        #[cfg(feature = "image-pe")]
+        #[allow(irrefutable_let_patterns)]
+        if let ImageType::PEImage(pe) = &self.image_type {
+            return pe.export_table.clone();
+        }
+        let mut exports = self
+            .exports
+            .iter()
+            .map(|(name, &address)| ExportEntry {
+                name: Some(name.clone()),
+                ordinal: None,
+                address: Some(address),
+                forwarder: None,
+            })
+            .collect::<Vec<_>>();
+        exports.sort_by(|a, b| a.name.cmp(&b.name));
+        exports
+    }
+
+    /// Nearest export or symbol at or before `address` within the same section, with the delta
+    /// from it. Used to render addresses like `GMalloc+0x12` rather than bare numbers
+    pub fn nearest_symbol(&self, address: usize) -> Option<(&str, usize)> {
+        let section = self.memory.get_section_containing(address).ok()?;
+        let floor = section.address();
+
+        let exports = self.exports.iter().map(|(name, &a)| (name.as_str(), a));
+        #[cfg(feature = "symbols")]
+        let exports = exports.chain(
+            self.symbols
+                .iter()
+                .flatten()
+                .map(|(&a, symbol)| (symbol.name.as_str(), a)),
+        );
+
+        exports
+            .filter(|&(_, a)| (floor..=address).contains(&a))
+            .max_by_key(|&(name, a)| (a, std::cmp::Reverse(name)))
+            .map(|(name, a)| (name, address - a))
+    }
+
+    /// Like [`Self::nearest_symbol`] but with the name demangled by `demangler`
+    #[cfg(feature = "symbols")]
+    pub fn nearest_symbol_demangled(
+        &self,
+        address: usize,
+        demangler: &dyn symbols::Demangler,
+    ) -> Option<(String, usize)> {
+        self.nearest_symbol(address).map(|(name, delta)| {
+            (
+                demangler.demangle(name).unwrap_or_else(|| name.to_string()),
+                delta,
+            )
+        })
+    }
+
+    /// Identifier of this build of the executable. Falls back to hashing executable sections if
+    /// the image has no embedded identifier so is stable across rebasing but not across
+    /// patching code
+    pub fn build_id(&self) -> BuildId {
+        use sha2::Digest;
+
+        if let Some(id) = &self.build_id {
+            return id.clone();
+        }
+        let mut hasher = sha2::Sha256::new();
+        for (_, data) in self.executable_bytes() {
+            hasher.update(data);
+        }
+        BuildId::TextSha256(hasher.finalize().into())
+    }
+
+    /// Hash of executable section contents which is unaffected by the address the image is
+    /// loaded at, used to decide whether cached offsets still apply to this exact build.
+    ///
+    /// Code is position independent apart from relocated absolute addresses, so relocations are
+    /// normalized without needing the relocation table: every 8 byte little endian value which
+    /// points into `[base, base + size)` is replaced by its offset from the base before hashing,
+    /// as is the address of each section. Images based at 0 are left as is since their absolute
+    /// addresses already are offsets. Rebuilt or patched code changes the fingerprint
+    pub fn text_fingerprint(&self) -> TextFingerprint {
+        use sha2::Digest;
+
+        let image = self.base()..self.base() + self.size();
+        let mut hasher = sha2::Sha256::new();
+        for (address, data) in self.executable_bytes() {
+            hasher.update((address - self.base()).to_le_bytes());
+
+            let mut normalized = data.to_vec();
+            let mut i = 0;
+            while let Some(bytes) = normalized.get(i..i + 8) {
+                let value = u64::from_le_bytes(bytes.try_into().unwrap()) as usize;
+                if image.start != 0 && image.contains(&value) {
+                    normalized[i..i + 8]
+                        .copy_from_slice(&((value - image.start) as u64).to_le_bytes());
+                    i += 8;
+                } else {
+                    i += 1;
+                }
+            }
+            hasher.update(&normalized);
+        }
+        TextFingerprint(hasher.finalize().into())
+    }
+
+    /// Copy of the image as it would be mapped at `new_base`: sections, exports, imports and
+    /// symbols are moved and every fixup of the PE base relocation directory is applied, so
+    /// decoded absolute and rip-relative addresses match a live process loaded at that base.
+    /// Only supported for PE images
+    pub fn rebase(&self, new_base: usize) -> Result<Image<'static>> {
+        match &self.image_type {
+            #[cfg(feature = "image-pe")]
+            ImageType::PEImage(pe) => self.rebase_pe(pe, new_base),
+            #[allow(unreachable_patterns)]
+            _ => anyhow::bail!("rebasing is only supported for PE images"),
+        }
+    }
+
+    /// Address the image is loaded at. For images read from disk this is the preferred base
+    pub fn base(&self) -> usize {
+        self.base_address
+    }
+    /// Size of the mapped image in bytes such that `[base, base + size)` covers every section.
+    /// Uses `SizeOfImage` from the optional header for PE images
+    pub fn size(&self) -> usize {
+        #[cfg(feature = "image-pe")]
+        #[allow(irrefutable_let_patterns)]
+        if let ImageType::PEImage(PEImage {
+            size_of_image: Some(size),
+            ..
+        }) = self.image_type
+        {
+            return size;
+        }
+        self.memory
+            .sections()
+            .iter()
+            .map(|s| s.address() + s.len())
+            .max()
+            .unwrap_or(self.base_address)
+            .saturating_sub(self.base_address)
+    }
+    /// Whether `address` lies within `[base, base + size)`
+    pub fn contains(&self, address: usize) -> bool {
+        (self.base()..self.base() + self.size()).contains(&address)
+    }
+
+    /// Iterate readable spans of executable sections as `(address, data)`. Unmapped, guard or
+    /// execute-only pages of a live image are never touched, see [`Image::unreadable_ranges`]
+    pub fn executable_bytes(&self) -> impl Iterator<Item = (usize, &[u8])> + '_ {
+        self.memory
+            .sections()
+            .iter()
+            .filter(|section| section.kind() == object::SectionKind::Text)
+            .flat_map(|section| {
+                let data = section.data();
+                self.readable_spans(section)
+                    .into_iter()
+                    .map(move |span| (section.address() + span.start, &data[span]))
+            })
+    }
+
+    /// Every RIP-relative reference from executable sections to a non-executable section as
+    /// `(instruction address, target address)` in instruction order, found by linearly
+    /// disassembling all code. This is expensive so the result is computed on first use and
+    /// cached for the lifetime of the image
+    pub fn global_xrefs(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.global_xrefs
+            .get_or_init(|| {
+                use iced_x86::{Decoder, DecoderOptions};
+
+                let is_data = |address: usize| {
+                    self.memory
+                        .get_section_containing(address)
+                        .is_ok_and(|s| s.kind() != object::SectionKind::Text)
+                };
+                let mut xrefs = vec![];
+                for (address, data) in self.executable_bytes() {
+                    let mut decoder =
+                        Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE);
+                    for inst in &mut decoder {
+                        if inst.is_ip_rel_memory_operand() {
+                            let target = inst.ip_rel_memory_address() as usize;
+                            if is_data(target) {
+                                xrefs.push((inst.ip() as usize, target));
+                            }
+                        }
+                    }
+                }
+                xrefs
+            })
+            .iter()
+            .copied()
+    }
+
+    /// Address ranges of sections which are skipped when scanning because they cannot be
+    /// safely read, e.g. guard or decommitted pages of a live image when built with
+    /// `process-internal`, or pages which could not be read by
+    /// [`crate::process::external::read_image_from_pid_tolerant`] and were zero filled
+    pub fn unreadable_ranges(&self) -> Vec<Range<usize>> {
+        let mut skipped = vec![];
+        for section in self.memory.sections() {
+            let mut offset = 0;
+            for span in self.readable_spans(section) {
+                if span.start > offset {
+                    skipped.push(section.address() + offset..section.address() + span.start);
+                }
+                offset = span.end;
+            }
+            if section.len() > offset {
+                skipped.push(section.address() + offset..section.address() + section.len());
+            }
+        }
+        skipped
+    }
+
+    /// Offsets of `section` which are safe to read and were actually captured, excluding
+    /// [`Image::unreadable_ranges`]
+    pub(crate) fn readable_spans(&self, section: &NamedMemorySection<'_>) -> Vec<Range<usize>> {
+        let spans = vec![0..section.len()];
+        if self.skipped.is_empty() {
+            return spans;
+        }
+        let mut holes = self
+            .skipped
+            .iter()
+            .filter(|r| r.start < section.address() + section.len() && r.end > section.address())
+            .map(|r| {
+                r.start.saturating_sub(section.address())
+                    ..(r.end - section.address()).min(section.len())
+            })
+            .collect::<Vec<_>>();
+        holes.sort_by_key(|r| r.start);
+        spans
+            .into_iter()
+            .flat_map(|span| {
+                let mut split = vec![];
+                let mut start = span.start;
+                for hole in &holes {
+                    if hole.end <= start || hole.start >= span.end {
+                        continue;
+                    }
+                    if hole.start > start {
+                        split.push(start..hole.start);
+                    }
+                    start = start.max(hole.end);
+                }
+                if start < span.end {
+                    split.push(start..span.end);
+                }
+                split
+            })
+            .collect()
+    }
+
+    /// Readable memory within `range` as `(address, data)` in address order, split wherever a
+    /// section ends or one of [`Image::unreadable_ranges`] begins
+    pub(crate) fn readable_slices(&self, range: Range<usize>) -> Vec<(usize, &[u8])> {
+        let mut slices = vec![];
+        for section in self.memory.sections() {
+            let data = section.data();
+            for span in self.readable_spans(section) {
+                let start = (section.address() + span.start).max(range.start);
+                let end = (section.address() + span.end).min(range.end);
+                if start < end {
+                    let offset = start - section.address();
+                    slices.push((start, &data[offset..offset + (end - start)]));
+                }
+            }
+        }
+        slices.sort_by_key(|(address, _)| *address);
+        slices
+    }
+
+    /// Scan readable memory within `range` for `pattern`, returning matches in address order.
+    /// Matches lie entirely within `range` and never touch [`Image::unreadable_ranges`]. A
+    /// `stride` other than 1 only tests aligned addresses, see
+    /// [`patternsleuth_scanner::scan_pattern_aligned`]
+    pub(crate) fn scan_readable(
+        &self,
+        pattern: &Pattern,
+        range: Range<usize>,
+        stride: usize,
+    ) -> Vec<usize> {
+        self.readable_slices(range)
+            .into_iter()
+            .flat_map(|(address, data)| {
+                if stride == 1 {
+                    patternsleuth_scanner::scan_pattern(&[pattern], address, data)
+                } else {
+                    patternsleuth_scanner::scan_pattern_aligned(&[pattern], address, data, stride)
+                }
+                .swap_remove(0)
+            })
+            .collect()
+    }
+
+    /// Enumerate null terminated printable strings of at least `min_len` characters contained
+    /// in read-only data sections
+    pub fn strings(
+        &self,
+        min_len: usize,
+    ) -> impl Iterator<Item = (usize, StringKind, Cow<'_, str>)> + '_ {
+        self.memory
+            .sections()
+            .iter()
+            .filter(|section| {
+                matches!(
+                    section.kind(),
+                    object::SectionKind::ReadOnlyData | object::SectionKind::ReadOnlyString
+                )
+            })
+            .flat_map(move |section| find_strings(section.address(), section.data(), min_len))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringKind {
+    Utf8,
+    Utf16,
+}
+
+fn is_printable(b: u8) -> bool {
+    matches!(b, 0x20..=0x7e | b'\t' | b'\n' | b'\r')
+}
+
+fn find_strings(
+    base_address: usize,
+    data: &[u8],
+    min_len: usize,
+) -> Vec<(usize, StringKind, Cow<'_, str>)> {
+    let min_len = min_len.max(1);
+    let mut strings = vec![];
+
+    // narrow
+    let mut i = 0;
+    while i < data.len() {
+        let len = data[i..].iter().take_while(|b| is_printable(**b)).count();
+        if len >= min_len && data.get(i + len) == Some(&0) {
+            let s = std::str::from_utf8(&data[i..i + len]).unwrap();
+            strings.push((base_address + i, StringKind::Utf8, Cow::Borrowed(s)));
+        }
+        i += len + 1;
+    }
+
+    // wide (only ASCII range code units, aligned to 2 bytes)
+    let mut i = base_address % 2;
+    while i + 1 < data.len() {
+        let len = data[i..]
+            .chunks_exact(2)
+            .take_while(|c| c[1] == 0 && is_printable(c[0]))
+            .count();
+        let end = i + len * 2;
+        if len >= min_len && data.get(end..end + 2) == Some(&[0, 0]) {
+            let s = data[i..end].iter().step_by(2).map(|b| *b as char).collect();
+            strings.push((base_address + i, StringKind::Utf16, Cow::Owned(s)));
+        }
+        i = end + 2;
+    }
+
+    strings
 }
 
 #[derive(Default)]
@@ -190,3 +743,47 @@ impl<P: AsRef<Path>> ImageBuilderWithSymbols<P> {
         Image::read(None, data, self.symbols, self.functions)
     }
 }
+
+/// Read exported symbols, relocating addresses to `base_address`
+fn read_exports<'data: 'file, 'file>(
+    object: &'file impl object::Object<'data, 'file>,
+    base_address: usize,
+) -> HashMap<String, usize> {
+    let relative_base = object.relative_address_base() as usize;
+    object
+        .exports()
+        .map(|exports| {
+            exports
+                .into_iter()
+                .filter_map(|e| {
+                    let name = std::str::from_utf8(e.name()).ok()?.to_owned();
+                    Some((
+                        name,
+                        (e.address() as usize)
+                            .wrapping_sub(relative_base)
+                            .wrapping_add(base_address),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Read the build identifier embedded in the image if any
+fn read_build_id<'data: 'file, 'file>(
+    object: &'file impl object::Object<'data, 'file>,
+) -> Option<BuildId> {
+    if let Ok(Some(info)) = object.pdb_info() {
+        return Some(BuildId::Pdb {
+            guid: info.guid(),
+            age: info.age(),
+        });
+    }
+    if let Ok(Some(id)) = object.build_id() {
+        return Some(BuildId::Gnu(id.to_vec()));
+    }
+    if let Ok(Some(uuid)) = object.mach_uuid() {
+        return Some(BuildId::Uuid(uuid));
+    }
+    None
+}
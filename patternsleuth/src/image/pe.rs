@@ -4,15 +4,25 @@ use std::ops::Range;
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
-use super::{Image, ImageType};
+use super::{ExportEntry, Image, ImageType};
 #[cfg(feature = "symbols")]
 use crate::symbols;
-use crate::{Memory, MemoryAccessError, MemoryAccessorTrait, MemoryTrait, RuntimeFunction};
+use crate::{
+    Memory, MemoryAccessError, MemoryAccessorTrait, MemoryTrait, NamedMemorySection,
+    RuntimeFunction,
+};
 use object::Object;
 
 pub struct PEImage {
     pub exception_directory_range: Range<usize>,
     pub exception_children_cache: HashMap<usize, Vec<RuntimeFunction>>,
+    /// `SizeOfImage` from the optional header
+    pub size_of_image: Option<usize>,
+    /// Full export table, see [`Image::export_manifest`]
+    pub export_table: Vec<ExportEntry>,
+    /// RVAs of the `IMAGE_REL_BASED_DIR64` fixups listed in the base relocation directory, see
+    /// [`Image::rebase`]
+    pub relocations: Vec<u32>,
 }
 
 impl PEImage {
@@ -24,7 +34,9 @@ impl PEImage {
         // place holder only
         let size = 12;
         let mut min = 0;
-        let mut max = self.exception_directory_range.len() / size - 1;
+        let Some(mut max) = (self.exception_directory_range.len() / size).checked_sub(1) else {
+            return Ok(None);
+        };
 
         while min <= max {
             let i = (max + min) / 2;
@@ -43,8 +55,10 @@ impl PEImage {
                 } else {
                     min = i + 1;
                 }
+            } else if let Some(i) = i.checked_sub(1) {
+                max = i;
             } else {
-                max = i - 1;
+                break;
             }
         }
         Ok(None)
@@ -164,6 +178,74 @@ impl PEImage {
 }
 
 impl Image<'_> {
+    /// See [`Image::rebase`]
+    pub(super) fn rebase_pe(&self, pe: &PEImage, new_base: usize) -> Result<Image<'static>> {
+        let delta = new_base.wrapping_sub(self.base_address);
+        let shift = |address: usize| address.wrapping_add(delta);
+
+        let fixups = pe
+            .relocations
+            .iter()
+            .map(|&rva| self.base_address + rva as usize)
+            .collect::<Vec<_>>();
+        let exception_directory = &pe.exception_directory_range;
+
+        let mut new = Image {
+            base_address: new_base,
+            memory: self.memory.rebased(delta, &fixups),
+            #[cfg(feature = "symbols")]
+            symbols: self.symbols.as_ref().map(|symbols| {
+                symbols
+                    .iter()
+                    .map(|(&address, symbol)| (shift(address), symbol.clone()))
+                    .collect()
+            }),
+            imports: self
+                .imports
+                .iter()
+                .map(|(lib, functions)| {
+                    let functions = functions
+                        .iter()
+                        .map(|(name, &address)| (name.clone(), shift(address)))
+                        .collect();
+                    (lib.clone(), functions)
+                })
+                .collect(),
+            exports: self
+                .exports
+                .iter()
+                .map(|(name, &address)| (name.clone(), shift(address)))
+                .collect(),
+            build_id: self.build_id.clone(),
+            global_xrefs: Default::default(),
+            skipped: self
+                .skipped
+                .iter()
+                .map(|r| shift(r.start)..shift(r.end))
+                .collect(),
+            image_type: ImageType::PEImage(PEImage {
+                exception_directory_range: shift(exception_directory.start)
+                    ..shift(exception_directory.end),
+                exception_children_cache: Default::default(),
+                size_of_image: pe.size_of_image,
+                export_table: pe
+                    .export_table
+                    .iter()
+                    .map(|e| ExportEntry {
+                        address: e.address.map(shift),
+                        ..e.clone()
+                    })
+                    .collect(),
+                relocations: pe.relocations.clone(),
+            }),
+        };
+
+        if !pe.exception_children_cache.is_empty() {
+            new.populate_exception_cache()?;
+        }
+        Ok(new)
+    }
+
     // this function is privately used by pe image
     fn populate_exception_cache(&mut self) -> Result<(), MemoryAccessError> {
         #[allow(irrefutable_let_patterns)]
@@ -261,7 +343,7 @@ impl PEImage {
 
                     let mut imports: HashMap<String, HashMap<String, usize>> = Default::default();
 
-                    let import_table = inner.import_table()?.unwrap();
+                    let import_table = inner.import_table()?.context("no import table")?;
                     let mut import_descs = import_table.descriptors()?;
 
                     while let Some(import_desc) = import_descs.next()? {
@@ -288,15 +370,84 @@ impl PEImage {
             })
         };
 
+        let get_size_of_image = || -> Option<usize> {
+            use object::read::pe::{ImageNtHeaders, ImageOptionalHeader};
+            match object {
+                object::File::Pe64(ref inner) => {
+                    Some(inner.nt_headers().optional_header().size_of_image() as usize)
+                }
+                _ => None,
+            }
+        };
+
+        let get_export_table = || -> Result<Vec<ExportEntry>> {
+            use object::read::pe::ExportTarget;
+            let object::File::Pe64(ref inner) = object else {
+                bail!("not a PE file");
+            };
+            let Some(table) = inner.export_table()? else {
+                return Ok(vec![]);
+            };
+            let string = |s: &[u8]| String::from_utf8_lossy(s).into_owned();
+            let mut exports = vec![];
+            for export in table.exports()? {
+                let (address, forwarder) = match export.target {
+                    ExportTarget::Address(rva) => (Some(base_address + rva as usize), None),
+                    ExportTarget::ForwardByName(lib, name) => {
+                        (None, Some(format!("{}.{}", string(lib), string(name))))
+                    }
+                    ExportTarget::ForwardByOrdinal(lib, ordinal) => {
+                        (None, Some(format!("{}.#{ordinal}", string(lib))))
+                    }
+                };
+                exports.push(ExportEntry {
+                    name: export.name.map(string),
+                    ordinal: Some(export.ordinal),
+                    address,
+                    forwarder,
+                });
+            }
+            exports.sort_by_key(|e| e.ordinal);
+            Ok(exports)
+        };
+
+        let get_relocations = || -> Result<Vec<u32>> {
+            let object::File::Pe64(ref inner) = object else {
+                bail!("not a PE file");
+            };
+            let mut relocations = vec![];
+            let Some(mut blocks) = inner
+                .data_directories()
+                .relocation_blocks(inner.data(), &inner.section_table())?
+            else {
+                return Ok(relocations);
+            };
+            while let Some(block) = blocks.next()? {
+                relocations.extend(
+                    block
+                        .filter(|r| r.typ == object::pe::IMAGE_REL_BASED_DIR64)
+                        .map(|r| r.virtual_address),
+                );
+            }
+            Ok(relocations)
+        };
+
         let mut new = Image {
             base_address,
             memory,
             #[cfg(feature = "symbols")]
             symbols,
             imports: get_imports().unwrap_or_default(),
+            exports: super::read_exports(&object, base_address),
+            build_id: super::read_build_id(&object),
+            global_xrefs: Default::default(),
+            skipped: Default::default(),
             image_type: ImageType::PEImage(PEImage {
                 exception_directory_range: get_ex_dir().unwrap_or_default(),
                 exception_children_cache: Default::default(),
+                size_of_image: get_size_of_image(),
+                export_table: get_export_table().unwrap_or_default(),
+                relocations: get_relocations().unwrap_or_default(),
             }),
         };
 
@@ -306,6 +457,29 @@ impl PEImage {
         Ok(new)
     }
 
+    /// Read a PE image from `data` in its mapped layout, i.e. section contents located at their
+    /// RVA rather than their file offset, with sections placed at `base + RVA`. See
+    /// [`Image::from_bytes_with_base`]
+    pub fn read_mapped(data: &[u8], base: usize) -> Result<Image<'_>, anyhow::Error> {
+        let object = object::File::parse(data)?;
+        let preferred_base = object.relative_address_base() as usize;
+        let sections = object
+            .sections()
+            .filter_map(|s| {
+                let rva = (s.address() as usize).checked_sub(preferred_base)?;
+                let mapped = data.get(rva..)?;
+                let len = (s.size() as usize).min(mapped.len());
+                Some(NamedMemorySection::new(
+                    s.name().ok()?.to_string(),
+                    base + rva,
+                    s.kind(),
+                    &mapped[..len],
+                ))
+            })
+            .collect::<Vec<_>>();
+        let memory = Memory::from_sections(sections);
+        Self::read_inner_memory::<&str>(base, None, false, memory, object)
+    }
     pub fn read_inner<P: AsRef<std::path::Path>>(
         base_addr: Option<usize>,
         exe_path: Option<P>,
@@ -1,3 +1,4 @@
+pub mod asm;
 pub mod image;
 pub mod process;
 pub mod resolvers;
@@ -210,8 +211,35 @@ pub trait MemoryTrait<'data> {
     fn range_to(&self, range: RangeTo<usize>) -> Result<&[u8], MemoryAccessError>;
 }
 
+/// Primitive integer types which can be read from little endian memory
+pub trait FromLeBytes: Sized {
+    const SIZE: usize;
+    /// Convert `bytes` to `Self`. `bytes` must be exactly `Self::SIZE` long
+    fn from_le_slice(bytes: &[u8]) -> Self;
+}
+macro_rules! impl_from_le_bytes {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl FromLeBytes for $ty {
+                const SIZE: usize = std::mem::size_of::<$ty>();
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    <$ty>::from_le_bytes(bytes.try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+impl_from_le_bytes!(u8, u16, u32, u64, i8, i16, i32, i64, usize, isize);
+
 /// Memory accessor helpers
 pub trait MemoryAccessorTrait<'data>: MemoryTrait<'data> {
+    /// Return little endian `T` at `address`
+    fn read_le<T: FromLeBytes>(&self, address: usize) -> Result<T, MemoryAccessError> {
+        let end = address
+            .checked_add(T::SIZE)
+            .ok_or(MemoryAccessError::MemoryOutOfBoundsError)?;
+        Ok(T::from_le_slice(self.range(address..end)?))
+    }
     /// Return i16 at `address`
     fn i16_le(&self, address: usize) -> Result<i16, MemoryAccessError> {
         Ok(i16::from_le_bytes(
@@ -292,20 +320,33 @@ impl<'data, T: MemoryTrait<'data>> MemoryAccessorTrait<'data> for T {}
 
 impl<'data, T: MemoryBlockTrait<'data>> MemoryTrait<'data> for T {
     fn index(&self, address: usize) -> Result<u8, MemoryAccessError> {
-        // TODO bounds
-        Ok(self.data()[address - self.address()])
+        address
+            .checked_sub(self.address())
+            .and_then(|i| self.data().get(i))
+            .copied()
+            .ok_or(MemoryAccessError::MemoryOutOfBoundsError)
     }
     fn range(&self, range: Range<usize>) -> Result<&[u8], MemoryAccessError> {
-        // TODO bounds
-        Ok(&self.data()[range.start - self.address()..range.end - self.address()])
+        let start = range.start.checked_sub(self.address());
+        let end = range.end.checked_sub(self.address());
+        start
+            .zip(end)
+            .and_then(|(start, end)| self.data().get(start..end))
+            .ok_or(MemoryAccessError::MemoryOutOfBoundsError)
     }
     fn range_from(&self, range: RangeFrom<usize>) -> Result<&[u8], MemoryAccessError> {
-        // TODO bounds
-        Ok(&self.data()[range.start - self.address()..])
+        range
+            .start
+            .checked_sub(self.address())
+            .and_then(|start| self.data().get(start..))
+            .ok_or(MemoryAccessError::MemoryOutOfBoundsError)
     }
     fn range_to(&self, range: RangeTo<usize>) -> Result<&[u8], MemoryAccessError> {
-        // TODO bounds
-        Ok(&self.data()[..range.end - self.address()])
+        range
+            .end
+            .checked_sub(self.address())
+            .and_then(|end| self.data().get(..end))
+            .ok_or(MemoryAccessError::MemoryOutOfBoundsError)
     }
 }
 
@@ -389,29 +430,59 @@ impl<'data> MemoryBlockTrait<'data> for NamedMemorySection<'data> {
     }
 }
 
+/// Identifies a section of [`Memory`] by its position and name
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SectionId {
+    /// Index of the section in [`Memory::sections`]
+    pub index: usize,
+    pub name: String,
+    pub kind: object::SectionKind,
+}
+
 pub struct Memory<'data> {
     sections: Vec<NamedMemorySection<'data>>,
 }
 
 impl<'data> Memory<'data> {
+    /// Read sections of `object`. Malformed section tables are tolerated: sections whose file
+    /// data lies outside of the buffer are skipped and PE sections are clamped to
+    /// `SizeOfImage`, see also [`Self::from_sections`]
     pub fn new(object: &File<'data>) -> Result<Self> {
-        Ok(Self {
-            sections: object
-                .sections()
-                .map(|s| {
-                    Ok(NamedMemorySection::new(
-                        s.name()?.to_string(),
-                        s.address() as usize,
-                        s.kind(),
-                        s.data()?,
-                    ))
-                })
-                .collect::<Result<Vec<_>>>()?,
-        })
+        // end of the mapped image, only known for PE
+        let limit = match object {
+            File::Pe64(pe) => {
+                use object::read::pe::{ImageNtHeaders, ImageOptionalHeader};
+                Some(
+                    (object.relative_address_base() as usize)
+                        .saturating_add(pe.nt_headers().optional_header().size_of_image() as usize),
+                )
+            }
+            _ => None,
+        };
+        Ok(Self::from_sections(object.sections().filter_map(|s| {
+            // ELF sections which are not loaded (.comment, .symtab, .debug_*) all sit at address
+            // 0 and would otherwise be mistaken for overlapping sections
+            if let object::SectionFlags::Elf { sh_flags } = s.flags() {
+                if sh_flags & u64::from(object::elf::SHF_ALLOC) == 0 {
+                    return None;
+                }
+            }
+            let data = s.data().ok()?;
+            let address = s.address() as usize;
+            let len = limit.map_or(data.len(), |limit| {
+                limit.saturating_sub(address).min(data.len())
+            });
+            Some(NamedMemorySection::new(
+                s.name().ok()?.to_string(),
+                address,
+                s.kind(),
+                &data[..len],
+            ))
+        })))
     }
     pub fn new_external_data(sections: Vec<(object::Section<'_, '_>, Vec<u8>)>) -> Result<Self> {
-        Ok(Self {
-            sections: sections
+        Ok(Self::from_sections(
+            sections
                 .into_iter()
                 .map(|(s, d)| {
                     Ok(NamedMemorySection::new(
@@ -422,13 +493,13 @@ impl<'data> Memory<'data> {
                     ))
                 })
                 .collect::<Result<Vec<_>>>()?,
-        })
+        ))
     }
     pub fn new_internal_data(
         sections: Vec<(object::Section<'_, '_>, &'data [u8])>,
     ) -> Result<Self> {
-        Ok(Self {
-            sections: sections
+        Ok(Self::from_sections(
+            sections
                 .into_iter()
                 .map(|(s, d)| {
                     Ok(NamedMemorySection::new(
@@ -439,7 +510,64 @@ impl<'data> Memory<'data> {
                     ))
                 })
                 .collect::<Result<Vec<_>>>()?,
-        })
+        ))
+    }
+    /// Drop empty sections and sections overlapping an earlier one so that every address
+    /// belongs to at most one section and is never scanned twice
+    fn from_sections(sections: impl IntoIterator<Item = NamedMemorySection<'data>>) -> Self {
+        let mut kept: Vec<NamedMemorySection<'data>> = vec![];
+        for section in sections {
+            let Some(end) = section.address().checked_add(section.len()) else {
+                continue;
+            };
+            if section.is_empty() {
+                continue;
+            }
+            if kept
+                .iter()
+                .any(|k| section.address() < k.address() + k.len() && k.address() < end)
+            {
+                tracing::warn!(
+                    "skipping section {} at {:#x} overlapping another section",
+                    section.name(),
+                    section.address()
+                );
+                continue;
+            }
+            kept.push(section);
+        }
+        Self { sections: kept }
+    }
+    /// Owned copy with every section moved by `delta` and the 8 byte absolute address at each
+    /// of `fixups` (addresses prior to moving) adjusted by `delta`
+    pub(crate) fn rebased(&self, delta: usize, fixups: &[usize]) -> Memory<'static> {
+        Memory {
+            sections: self
+                .sections
+                .iter()
+                .map(|section| {
+                    let mut data = section.data().to_vec();
+                    for &fixup in fixups {
+                        let Some(offset) = fixup
+                            .checked_sub(section.address())
+                            .filter(|offset| offset + 8 <= data.len())
+                        else {
+                            continue;
+                        };
+                        let value =
+                            u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+                        data[offset..offset + 8]
+                            .copy_from_slice(&value.wrapping_add(delta as u64).to_le_bytes());
+                    }
+                    NamedMemorySection::new(
+                        section.name.clone(),
+                        section.address().wrapping_add(delta),
+                        section.kind,
+                        data,
+                    )
+                })
+                .collect(),
+        }
     }
     pub fn sections(&self) -> &[NamedMemorySection] {
         &self.sections
@@ -456,6 +584,18 @@ impl<'data> Memory<'data> {
             })
             .ok_or(MemoryAccessError::MemoryOutOfBoundsError)
     }
+    /// [`SectionId`] of the section containing `address`
+    pub fn section_id(&self, address: usize) -> Option<SectionId> {
+        self.sections
+            .iter()
+            .enumerate()
+            .find(|(_, s)| (s.address()..s.address() + s.len()).contains(&address))
+            .map(|(index, s)| SectionId {
+                index,
+                name: s.name().to_string(),
+                kind: s.kind(),
+            })
+    }
     pub fn find<F>(&self, kind: object::SectionKind, filter: F) -> Option<usize>
     where
         F: Fn(usize, &[u8]) -> bool,
@@ -540,8 +680,11 @@ impl<'data> Matchable<'data> for Memory<'data> {
         address: usize,
     ) -> Result<Option<Vec<patternsleuth_scanner::Capture<'data>>>, MemoryAccessError> {
         let s = self.get_section_containing(address)?;
-        // TODO bounds check data passed to captures
-        Ok(pattern.captures(s.data(), s.address(), address - s.address()))
+        let index = address - s.address();
+        if index + pattern.simple.len() > s.len() {
+            return Ok(None);
+        }
+        Ok(pattern.captures(s.data(), s.address(), index))
     }
 }
 
@@ -726,3 +869,692 @@ pub mod disassemble {
         Ok(())
     }
 }
+
+#[cfg(all(test, feature = "image-pe"))]
+mod test {
+    use super::*;
+
+    pub(crate) const IMAGE_BASE: usize = 0x140000000;
+
+    pub(crate) struct Section {
+        pub(crate) name: &'static str,
+        pub(crate) rva: u32,
+        pub(crate) virtual_size: u32,
+        pub(crate) raw_offset: u32,
+        pub(crate) raw_size: u32,
+    }
+
+    /// Build a minimal PE32+ image with the given section table. Section contents are `0xcc`
+    /// wherever they fall within the returned buffer. `.data` is writable data, every other
+    /// section is code
+    pub(crate) fn build_pe(size_of_image: u32, sections: &[Section]) -> Vec<u8> {
+        let mut data = vec![0; 0x400];
+        let put16 = |data: &mut Vec<u8>, at: usize, v: u16| {
+            data[at..at + 2].copy_from_slice(&v.to_le_bytes())
+        };
+        let put32 = |data: &mut Vec<u8>, at: usize, v: u32| {
+            data[at..at + 4].copy_from_slice(&v.to_le_bytes())
+        };
+
+        data[0..2].copy_from_slice(b"MZ");
+        put32(&mut data, 0x3c, 0x40);
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+
+        // file header
+        put16(&mut data, 0x44, object::pe::IMAGE_FILE_MACHINE_AMD64);
+        put16(&mut data, 0x46, sections.len() as u16);
+        put16(&mut data, 0x54, 0xf0);
+        put16(&mut data, 0x56, 0x22);
+
+        // optional header
+        let opt = 0x58;
+        put16(&mut data, opt, object::pe::IMAGE_NT_OPTIONAL_HDR64_MAGIC);
+        data[opt + 24..opt + 32].copy_from_slice(&(IMAGE_BASE as u64).to_le_bytes());
+        put32(&mut data, opt + 32, 0x1000);
+        put32(&mut data, opt + 36, 0x200);
+        put32(&mut data, opt + 56, size_of_image);
+        put32(&mut data, opt + 60, 0x400);
+        put16(&mut data, opt + 68, 3);
+        put32(&mut data, opt + 108, 16);
+
+        let table = opt + 0xf0;
+        for (i, s) in sections.iter().enumerate() {
+            let h = table + i * 40;
+            data[h..h + s.name.len()].copy_from_slice(s.name.as_bytes());
+            put32(&mut data, h + 8, s.virtual_size);
+            put32(&mut data, h + 12, s.rva);
+            put32(&mut data, h + 16, s.raw_size);
+            put32(&mut data, h + 20, s.raw_offset);
+            let characteristics = match s.name {
+                ".data" => 0xc0000040,
+                _ => 0x60000020,
+            };
+            put32(&mut data, h + 36, characteristics);
+        }
+
+        let end = sections
+            .iter()
+            .map(|s| (s.raw_offset + s.raw_size) as usize)
+            .filter(|&end| end <= 0x2000)
+            .max()
+            .unwrap_or(0)
+            .max(data.len());
+        data.resize(end, 0);
+        for s in sections {
+            let start = (s.raw_offset as usize).min(data.len());
+            let end = ((s.raw_offset + s.raw_size) as usize).min(data.len());
+            data[start..end].fill(0xcc);
+        }
+        data
+    }
+
+    fn names<'a>(image: &'a Image<'_>) -> Vec<&'a str> {
+        image.memory.sections().iter().map(|s| s.name()).collect()
+    }
+
+    #[test]
+    fn test_malformed_sections() {
+        let data = build_pe(
+            0x5000,
+            &[
+                Section {
+                    name: ".text",
+                    rva: 0x1000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x400,
+                    raw_size: 0x200,
+                },
+                // file data far past the end of the buffer
+                Section {
+                    name: ".oob",
+                    rva: 0x2000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x100000,
+                    raw_size: 0x200,
+                },
+                Section {
+                    name: ".empty",
+                    rva: 0x3000,
+                    virtual_size: 0,
+                    raw_offset: 0,
+                    raw_size: 0,
+                },
+                // overlaps .text
+                Section {
+                    name: ".dup",
+                    rva: 0x1100,
+                    virtual_size: 0x200,
+                    raw_offset: 0x400,
+                    raw_size: 0x200,
+                },
+            ],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        assert_eq!(vec![".text"], names(&image));
+        let text = &image.memory.sections()[0];
+        assert_eq!(IMAGE_BASE + 0x1000, text.address());
+        assert_eq!(0x200, text.len());
+    }
+
+    #[test]
+    fn test_elf_non_alloc_sections() {
+        let mut data = vec![0; 0x380];
+        let put16 = |data: &mut Vec<u8>, at: usize, v: u16| {
+            data[at..at + 2].copy_from_slice(&v.to_le_bytes())
+        };
+        let put32 = |data: &mut Vec<u8>, at: usize, v: u32| {
+            data[at..at + 4].copy_from_slice(&v.to_le_bytes())
+        };
+        let put64 = |data: &mut Vec<u8>, at: usize, v: u64| {
+            data[at..at + 8].copy_from_slice(&v.to_le_bytes())
+        };
+
+        data[0..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+        put16(&mut data, 16, object::elf::ET_DYN);
+        put16(&mut data, 18, object::elf::EM_X86_64);
+        put32(&mut data, 20, 1);
+        put64(&mut data, 40, 0x240);
+        put16(&mut data, 52, 64);
+        put16(&mut data, 58, 64);
+        put16(&mut data, 60, 5);
+        put16(&mut data, 62, 4);
+
+        let strtab = b"\0.text\0.comment\0.debug_str\0.shstrtab\0";
+        data[0x40..0x40 + strtab.len()].copy_from_slice(strtab);
+        data[0x100..0x200].fill(0xcc);
+
+        // (name, type, flags, address, offset, size) after the null section. Only .text is
+        // loaded, the rest all sit at address 0
+        let sections = [
+            (1, object::elf::SHT_PROGBITS, 0x6, 0x1000, 0x100, 0x100),
+            (7, object::elf::SHT_PROGBITS, 0x30, 0, 0x200, 0x10),
+            (16, object::elf::SHT_PROGBITS, 0x30, 0, 0x210, 0x20),
+            (27, object::elf::SHT_STRTAB, 0, 0, 0x40, strtab.len() as u64),
+        ];
+        for (i, (name, kind, flags, address, offset, size)) in sections.into_iter().enumerate() {
+            let h = 0x240 + (i + 1) * 64;
+            put32(&mut data, h, name);
+            put32(&mut data, h + 4, kind);
+            put64(&mut data, h + 8, flags);
+            put64(&mut data, h + 16, address);
+            put64(&mut data, h + 24, offset);
+            put64(&mut data, h + 32, size);
+        }
+
+        let object = object::File::parse(&*data).unwrap();
+        let memory = Memory::new(&object).unwrap();
+        let names = memory
+            .sections()
+            .iter()
+            .map(|s| s.name())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![".text"], names);
+        assert_eq!(0x1000, memory.sections()[0].address());
+        assert_eq!(0x100, memory.sections()[0].len());
+    }
+
+    #[test]
+    fn test_section_clamped_to_image() {
+        let data = build_pe(
+            0x1100,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        assert_eq!(0x100, image.memory.sections()[0].len());
+    }
+
+    #[test]
+    fn test_from_bytes_with_base() {
+        let file = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        // lay the file out as it would be mapped into memory
+        let mut mapped = vec![0; 0x2000];
+        mapped[..0x400].copy_from_slice(&file[..0x400]);
+        mapped[0x1000..0x1200].copy_from_slice(&file[0x400..0x600]);
+        mapped[0x1010] = 0x90;
+
+        let base = 0x7ff6_1234_0000;
+        let image = Image::from_bytes_with_base(&mapped, base, image::ImageFormat::Pe).unwrap();
+        assert_eq!(base, image.base_address);
+        assert_eq!(vec![".text"], names(&image));
+        let text = &image.memory.sections()[0];
+        assert_eq!(base + 0x1000, text.address());
+        assert_eq!(0x200, text.len());
+        assert_eq!(0x90, image.memory[base + 0x1010]);
+        assert_eq!(0xcc, image.memory[base + 0x1000]);
+
+        assert!(Image::from_bytes_with_base(&mapped, base, image::ImageFormat::Elf).is_err());
+        assert!(
+            Image::from_bytes_with_base(&mapped[..0x40], base, image::ImageFormat::Pe).is_err()
+        );
+    }
+
+    #[cfg(feature = "image-elf")]
+    #[test]
+    fn test_from_bytes_with_base_elf() {
+        let mut mapped = vec![0; 0x1000];
+        let put16 = |data: &mut Vec<u8>, at: usize, v: u16| {
+            data[at..at + 2].copy_from_slice(&v.to_le_bytes())
+        };
+        let put32 = |data: &mut Vec<u8>, at: usize, v: u32| {
+            data[at..at + 4].copy_from_slice(&v.to_le_bytes())
+        };
+        let put64 = |data: &mut Vec<u8>, at: usize, v: u64| {
+            data[at..at + 8].copy_from_slice(&v.to_le_bytes())
+        };
+
+        mapped[0..7].copy_from_slice(b"\x7fELF\x02\x01\x01");
+        put16(&mut mapped, 16, object::elf::ET_DYN);
+        put16(&mut mapped, 18, object::elf::EM_X86_64);
+        put32(&mut mapped, 20, 1);
+        put64(&mut mapped, 24, 0x400);
+        put64(&mut mapped, 32, 0x40);
+        put16(&mut mapped, 52, 64);
+        put16(&mut mapped, 54, 56);
+        put16(&mut mapped, 56, 2);
+
+        // (type, flags, vaddr, size), mapped at their vaddr. No section headers as is usual for
+        // an image read from memory
+        let segments = [
+            (
+                object::elf::PT_LOAD,
+                object::elf::PF_R | object::elf::PF_X,
+                0,
+                0x1000,
+            ),
+            (object::elf::PT_GNU_EH_FRAME, object::elf::PF_R, 0x800, 12),
+        ];
+        for (i, (kind, flags, vaddr, size)) in segments.into_iter().enumerate() {
+            let h = 0x40 + i * 56;
+            put32(&mut mapped, h, kind);
+            put32(&mut mapped, h + 4, flags);
+            put64(&mut mapped, h + 8, vaddr);
+            put64(&mut mapped, h + 16, vaddr);
+            put64(&mut mapped, h + 32, size);
+            put64(&mut mapped, h + 40, size);
+        }
+        // .eh_frame_hdr pointing at an empty .eh_frame at 0x900
+        mapped[0x800..0x804].copy_from_slice(&[1, 0x1b, 0x03, 0x3b]);
+        put32(&mut mapped, 0x804, 0x900 - 0x804);
+        mapped[0x400] = 0x90;
+
+        let base = 0x7f12_3456_0000;
+        let image = Image::from_bytes_with_base(&mapped, base, image::ImageFormat::Elf).unwrap();
+        assert_eq!(base, image.base_address);
+        assert_eq!(vec![".text"], names(&image));
+        let text = &image.memory.sections()[0];
+        assert_eq!(base, text.address());
+        assert_eq!(0x1000, text.len());
+        assert_eq!(object::SectionKind::Text, text.kind());
+        assert_eq!(0x90, image.memory[base + 0x400]);
+
+        assert!(Image::from_bytes_with_base(&mapped, base, image::ImageFormat::Pe).is_err());
+    }
+
+    #[test]
+    fn test_unreadable_ranges() {
+        let mut data = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        // one match in a hole, one straddling the start of a hole and one readable
+        for offset in [0x50, 0x7e, 0x100] {
+            data[0x400 + offset..0x400 + offset + 4].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        }
+        let mut image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let text = IMAGE_BASE + 0x1000;
+        assert!(image.unreadable_ranges().is_empty());
+
+        // ranges skipped while reading a live process, including one straddling the section end
+        image.skipped = vec![text + 0x180..text + 0x300, text + 0x40..text + 0x80];
+        assert_eq!(
+            vec![text + 0x40..text + 0x80, text + 0x180..text + 0x200],
+            image.unreadable_ranges()
+        );
+        assert_eq!(
+            vec![(text, 0x40), (text + 0x80, 0x100)],
+            image
+                .executable_bytes()
+                .map(|(address, data)| (address, data.len()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(text + 0x20, 0x20), (text + 0x80, 0x10)],
+            image
+                .readable_slices(text + 0x20..text + 0x90)
+                .into_iter()
+                .map(|(address, data)| (address, data.len()))
+                .collect::<Vec<_>>()
+        );
+        let pattern = Pattern::new("de ad be ef").unwrap();
+        assert_eq!(
+            vec![text + 0x100],
+            image.scan_readable(&pattern, 0..usize::MAX, 1)
+        );
+        assert_eq!(
+            vec![text + 0x100],
+            image.scan_readable(&pattern, text..text + 0x200, 4)
+        );
+
+        let new_base = 0x7ff6_1234_0000;
+        let rebased = image.rebase(new_base).unwrap();
+        let text = new_base + 0x1000;
+        assert_eq!(
+            vec![text + 0x40..text + 0x80, text + 0x180..text + 0x200],
+            rebased.unreadable_ranges()
+        );
+    }
+
+    #[test]
+    fn test_rebase() {
+        let mut data = build_pe(
+            0x3000,
+            &[
+                Section {
+                    name: ".text",
+                    rva: 0x1000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x400,
+                    raw_size: 0x200,
+                },
+                Section {
+                    name: ".reloc",
+                    rva: 0x2000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x600,
+                    raw_size: 0x200,
+                },
+            ],
+        );
+        // absolute pointer to rva 0x1010 stored at rva 0x1008
+        data[0x408..0x410].copy_from_slice(&(IMAGE_BASE as u64 + 0x1010).to_le_bytes());
+        // single relocation block for page 0x1000 with one DIR64 entry and one padding entry
+        data[0x600..0x604].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[0x604..0x608].copy_from_slice(&12u32.to_le_bytes());
+        data[0x608..0x60a].copy_from_slice(&((10 << 12) | 0x008u16).to_le_bytes());
+        data[0x60a..0x60c].copy_from_slice(&0u16.to_le_bytes());
+        let opt = 0x58;
+        data[opt + 152..opt + 156].copy_from_slice(&0x2000u32.to_le_bytes());
+        data[opt + 156..opt + 160].copy_from_slice(&12u32.to_le_bytes());
+
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let new_base = 0x7ff6_1234_0000;
+        let rebased = image.rebase(new_base).unwrap();
+
+        let read_u64 = |image: &Image<'_>, address: usize| {
+            u64::from_le_bytes(image.memory[address..address + 8].try_into().unwrap())
+        };
+        assert_eq!(new_base, rebased.base_address);
+        assert_eq!(new_base + 0x1000, rebased.memory.sections()[0].address());
+        assert_eq!(
+            new_base as u64 + 0x1010,
+            read_u64(&rebased, new_base + 0x1008)
+        );
+        // bytes without a fixup are untouched
+        assert_eq!(
+            image.memory[IMAGE_BASE + 0x1010..IMAGE_BASE + 0x1200],
+            rebased.memory[new_base + 0x1010..new_base + 0x1200]
+        );
+
+        // rebasing back matches the original image
+        let restored = rebased.rebase(IMAGE_BASE).unwrap();
+        assert_eq!(
+            image.memory[IMAGE_BASE + 0x1000..IMAGE_BASE + 0x1200],
+            restored.memory[IMAGE_BASE + 0x1000..IMAGE_BASE + 0x1200]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        use crate::resolvers::{
+            snapshot::Snapshot,
+            unreal::{gengine::GEngine, KismetSystemLibrary, A},
+            Resolution, ResolveError,
+        };
+        use std::sync::Arc;
+
+        let data = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let rebased = image.rebase(0x7ff6_1234_0000).unwrap();
+
+        let snapshot = |image: &Image<'_>, names: &[&str]| {
+            let base = image.base_address;
+            let results: [resolvers::Result<Arc<dyn Resolution>>; 3] = [
+                Ok(Arc::new(GEngine(base + 0x1010))),
+                Ok(Arc::new(KismetSystemLibrary(
+                    names
+                        .iter()
+                        .enumerate()
+                        .map(|(i, name)| (name.to_string(), base + 0x1100 + i * 8))
+                        .collect(),
+                ))),
+                Err(ResolveError::Msg("not found".into())),
+            ];
+            Snapshot::from_results(
+                image,
+                ["GEngine", "KismetSystemLibrary", "Missing"]
+                    .into_iter()
+                    .zip(results.iter()),
+            )
+        };
+
+        // independent of map iteration order and of the base the image is loaded at
+        let a = snapshot(&image, &["A", "B", "C", "D"]);
+        let b = snapshot(&rebased, &["A", "B", "C", "D"]);
+        assert_eq!(a.to_string(), b.to_string());
+        assert_eq!(
+            "GEngine rva 0x1010\n\
+             KismetSystemLibrary map A=0x1100 B=0x1108 C=0x1110 D=0x1118\n\
+             Missing error not found\n",
+            a.to_string()
+        );
+        assert_eq!(a, a.to_string().parse::<Snapshot>().unwrap());
+
+        // other results are recorded through their own hook, relative to the base
+        let strings = |image: &Image<'_>| {
+            let base = image.base_address;
+            let result: resolvers::Result<Arc<dyn Resolution>> =
+                Ok(Arc::new(A([base + 0x1020, base + 0x1008]
+                    .into_iter()
+                    .collect())));
+            Snapshot::from_results(image, [("A", &result)]).to_string()
+        };
+        assert_eq!("A value 0x1008 0x1020\n", strings(&image));
+        assert_eq!(strings(&image), strings(&rebased));
+
+        // resolvers failing against the crafted image round trip as errors
+        let resolvers = resolvers::resolvers().take(4).collect::<Vec<_>>();
+        let captured = Snapshot::capture(&image, &resolvers);
+        assert_eq!(resolvers.len(), captured.entries.len());
+        assert_eq!(captured, captured.to_string().parse::<Snapshot>().unwrap());
+    }
+
+    #[test]
+    fn test_max_scan_concurrency() {
+        let data = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let eval_threads = || image.eval(|_| Box::pin(async { rayon::current_num_threads() }));
+
+        resolvers::set_max_scan_concurrency(2);
+        assert_eq!(2, eval_threads());
+        // explicit thread counts are capped by the limit
+        assert_eq!(2, resolvers::on_scan_pool(8, rayon::current_num_threads));
+        // nested calls stay on the pool they are made from
+        let nested = resolvers::on_scan_pool(1, || {
+            let inner = resolvers::on_scan_pool(2, rayon::current_num_threads);
+            (eval_threads(), inner)
+        });
+        assert_eq!((1, 1), nested);
+
+        resolvers::set_max_scan_concurrency(0);
+        assert_eq!(resolvers::max_scan_concurrency(), eval_threads());
+    }
+
+    #[derive(Debug, PartialEq)]
+    #[cfg_attr(
+        feature = "serde-resolvers",
+        derive(serde::Serialize, serde::Deserialize)
+    )]
+    struct VerifiedGlobal(usize);
+    resolvers::impl_resolver_singleton!(all, VerifiedGlobal, verify = "cc cc", |_ctx| async {
+        Ok(VerifiedGlobal(0))
+    });
+
+    #[test]
+    fn test_pre_resolved_verified() {
+        let data = build_pe(
+            0x3000,
+            &[
+                Section {
+                    name: ".text",
+                    rva: 0x1000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x400,
+                    raw_size: 0x200,
+                },
+                Section {
+                    name: ".data",
+                    rva: 0x2000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x600,
+                    raw_size: 0x200,
+                },
+            ],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let resolve = |address: usize| {
+            let mut pre_resolved = resolvers::PreResolved::new();
+            pre_resolved.insert("VerifiedGlobal", address);
+            resolvers::resolve_with(&image, VerifiedGlobal::resolver(), pre_resolved)
+                .unwrap()
+                .0
+        };
+
+        let data = IMAGE_BASE + 0x2000;
+        assert_eq!(data, resolve(data));
+        // rejected addresses fall back to the resolver, which returns 0
+        assert_eq!(0, resolve(data + 0x1ff), "verification pattern must match");
+        assert_eq!(
+            0,
+            resolve(IMAGE_BASE + 0x1000),
+            "code must start a known function"
+        );
+        assert_eq!(
+            0,
+            resolve(IMAGE_BASE + 0x4000),
+            "address must lie within the image"
+        );
+    }
+
+    #[test]
+    fn test_jump_table() {
+        let mut data = build_pe(
+            0x3000,
+            &[
+                Section {
+                    name: ".text",
+                    rva: 0x1000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x400,
+                    raw_size: 0x200,
+                },
+                Section {
+                    name: ".pdata",
+                    rva: 0x2000,
+                    virtual_size: 0x200,
+                    raw_offset: 0x600,
+                    raw_size: 0x200,
+                },
+            ],
+        );
+        let le = |values: &[i32]| {
+            values
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>()
+        };
+        // exception directory with one entry per function
+        data[0x58 + 136..0x58 + 144].copy_from_slice(&le(&[0x2000, 24]));
+
+        let mut put = |rva: usize, bytes: &[u8]| {
+            let offset = if rva >= 0x2000 {
+                rva - 0x1a00
+            } else {
+                rva - 0xc00
+            };
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        };
+        put(
+            0x2000,
+            &le(&[0x1000, 0x1100, 0x2180, 0x1100, 0x1200, 0x2180]),
+        );
+
+        // MSVC: image base relative entries
+        put(
+            0x1000,
+            &[
+                0x83, 0xf9, 0x03, // cmp ecx, 3
+                0x77, 0x10, // ja default
+                0x48, 0x8d, 0x15, 0xf4, 0xef, 0xff, 0xff, // lea rdx, [__ImageBase]
+                0x8b, 0x84, 0x8a, 0x80, 0x20, 0x00, 0x00, // mov eax, [rdx + rcx*4 + 0x2080]
+                0x48, 0x01, 0xd0, // add rax, rdx
+                0xff, 0xe0, // jmp rax
+            ],
+        );
+        // the entry following the table lies past the bounds check
+        put(0x2080, &le(&[0x1040, 0x1050, 0x1060, 0x1070, 0x1080]));
+
+        // clang: table relative entries
+        put(
+            0x1100,
+            &[
+                0x83, 0xf9, 0x01, // cmp ecx, 1
+                0x77, 0x10, // ja default
+                0x48, 0x8d, 0x05, 0xb4, 0x0f, 0x00, 0x00, // lea rax, [0x20c0]
+                0x48, 0x63, 0x0c, 0x88, // movsxd rcx, [rax + rcx*4]
+                0x48, 0x01, 0xc1, // add rcx, rax
+                0xff, 0xe1, // jmp rcx
+            ],
+        );
+        put(0x20c0, &le(&[0x1140 - 0x20c0, 0x1150 - 0x20c0]));
+
+        let mut image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let jump_table = |image: &Image<'_>, function: usize| {
+            image.eval(|ctx| Box::pin(async move { ctx.jump_table(IMAGE_BASE + function) }))
+        };
+        assert_eq!(
+            vec![0x1040, 0x1050, 0x1060, 0x1070],
+            jump_table(&image, 0x1000)
+                .unwrap()
+                .into_iter()
+                .map(|t| t - IMAGE_BASE)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![IMAGE_BASE + 0x1140, IMAGE_BASE + 0x1150],
+            jump_table(&image, 0x1100).unwrap()
+        );
+
+        // tables which are not readable are rejected rather than read
+        image.skipped = vec![IMAGE_BASE + 0x2080..IMAGE_BASE + 0x2090];
+        assert!(jump_table(&image, 0x1000).is_err());
+        assert!(jump_table(&image, 0x1100).is_ok());
+    }
+
+    #[test]
+    fn test_captures_at_section_end() {
+        let data = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let pattern = Pattern::new("cc cc").unwrap();
+        let last = IMAGE_BASE + 0x1000 + 0x1ff;
+        assert!(image.memory.captures(&pattern, last).unwrap().is_none());
+        assert!(image.memory.captures(&pattern, last - 1).unwrap().is_some());
+    }
+}
@@ -1,3 +1,168 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    sync::Mutex,
+};
+
+use anyhow::Result;
+
+/// Source of raw memory reads from another process
+pub trait RawReader {
+    /// Fill entire `buffer` with memory starting at `address`
+    fn read_raw(&self, address: usize, buffer: &mut [u8]) -> Result<()>;
+}
+
+pub const PAGE_SIZE: usize = 0x1000;
+
+#[derive(Default)]
+struct PageCache {
+    /// page address -> (page data, last access tick)
+    pages: HashMap<usize, (Box<[u8]>, u64)>,
+    /// last access tick -> page address, used to find least recently used page
+    lru: BTreeMap<u64, usize>,
+    tick: u64,
+}
+
+/// Page-aligned LRU cache in front of a [`RawReader`] so repeated reads of the same region do
+/// not each incur a syscall. Pages must be invalidated if the target may have modified them
+pub struct CachedReader<R> {
+    reader: R,
+    capacity: usize,
+    cache: Mutex<PageCache>,
+}
+
+impl<R: RawReader> CachedReader<R> {
+    /// Default number of cached pages (16 MiB)
+    pub const DEFAULT_CAPACITY: usize = 0x1000;
+
+    pub fn new(reader: R) -> Self {
+        Self::with_capacity(reader, Self::DEFAULT_CAPACITY)
+    }
+    /// Create reader caching at most `capacity` pages
+    pub fn with_capacity(reader: R, capacity: usize) -> Self {
+        Self {
+            reader,
+            capacity: capacity.max(1),
+            cache: Default::default(),
+        }
+    }
+    pub fn inner(&self) -> &R {
+        &self.reader
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Change cache capacity, evicting least recently used pages if necessary
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let cache = self.cache.get_mut().unwrap();
+        while cache.pages.len() > self.capacity {
+            Self::evict(cache);
+        }
+    }
+    /// Drop all cached pages overlapping `range`
+    pub fn invalidate(&self, range: Range<usize>) {
+        let mut cache = self.cache.lock().unwrap();
+        let mut page = range.start & !(PAGE_SIZE - 1);
+        while page < range.end {
+            if let Some((_, tick)) = cache.pages.remove(&page) {
+                cache.lru.remove(&tick);
+            }
+            page = match page.checked_add(PAGE_SIZE) {
+                Some(page) => page,
+                None => break,
+            };
+        }
+    }
+    /// Drop all cached pages
+    pub fn invalidate_all(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.pages.clear();
+        cache.lru.clear();
+    }
+    /// Read `buffer.len()` bytes starting at `address`, fetching missing pages from the reader
+    pub fn read(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+        let mut cache = self.cache.lock().unwrap();
+        let mut offset = 0;
+        while offset < buffer.len() {
+            let current = address + offset;
+            let page = current & !(PAGE_SIZE - 1);
+            let page_offset = current - page;
+            let len = (PAGE_SIZE - page_offset).min(buffer.len() - offset);
+
+            let data = self.page(&mut cache, page)?;
+            buffer[offset..offset + len].copy_from_slice(&data[page_offset..page_offset + len]);
+            offset += len;
+        }
+        Ok(())
+    }
+    fn page<'c>(&self, cache: &'c mut PageCache, page: usize) -> Result<&'c [u8]> {
+        cache.tick += 1;
+        let tick = cache.tick;
+        if let Some((_, last)) = cache.pages.get_mut(&page) {
+            let last = std::mem::replace(last, tick);
+            cache.lru.remove(&last);
+        } else {
+            let mut data = vec![0; PAGE_SIZE].into_boxed_slice();
+            self.reader.read_raw(page, &mut data)?;
+            if cache.pages.len() >= self.capacity {
+                Self::evict(cache);
+            }
+            cache.pages.insert(page, (data, tick));
+        }
+        cache.lru.insert(tick, page);
+        Ok(&cache.pages[&page].0)
+    }
+    fn evict(cache: &mut PageCache) {
+        if let Some((_, page)) = cache.lru.pop_first() {
+            cache.pages.remove(&page);
+        }
+    }
+}
+impl<R: RawReader> RawReader for CachedReader<R> {
+    fn read_raw(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+        self.read(address, buffer)
+    }
+}
+
+/// Read `buffer.len()` bytes starting at `address`, zero filling any pages that cannot be read
+/// rather than failing. Pages of a live process may be unmapped at any point so this allows a
+/// scan to continue over whatever remains readable. Returns the unreadable ranges, merged and in
+/// ascending order
+pub fn read_skipping_unreadable<R: RawReader + ?Sized>(
+    reader: &R,
+    address: usize,
+    buffer: &mut [u8],
+) -> Vec<Range<usize>> {
+    if reader.read_raw(address, buffer).is_ok() {
+        return vec![];
+    }
+
+    let mut skipped: Vec<Range<usize>> = vec![];
+    let mut offset = 0;
+    while offset < buffer.len() {
+        let current = address + offset;
+        let len = (PAGE_SIZE - (current & (PAGE_SIZE - 1))).min(buffer.len() - offset);
+        let chunk = &mut buffer[offset..offset + len];
+        if reader.read_raw(current, chunk).is_err() {
+            chunk.fill(0);
+            match skipped.last_mut() {
+                Some(last) if last.end == current => last.end = current + len,
+                _ => skipped.push(current..current + len),
+            }
+        }
+        offset += len;
+    }
+    skipped
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn warn_skipped(skipped: &[Range<usize>]) {
+    for range in skipped {
+        tracing::warn!("skipped unreadable region {range:x?}");
+    }
+}
+
 #[cfg(target_os = "linux")]
 pub use linux::*;
 
@@ -10,6 +175,24 @@ mod linux {
 
     use crate::{image, Image, Memory};
 
+    /// Live process memory read via `process_vm_readv`
+    pub struct Process {
+        pub pid: i32,
+    }
+    impl super::RawReader for Process {
+        fn read_raw(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+            let read = read_process_mem(self.pid, address, buffer)?;
+            if read != buffer.len() {
+                bail!(
+                    "short read PID={} addr=0x{address:x} ({read} of {} bytes)",
+                    self.pid,
+                    buffer.len()
+                );
+            }
+            Ok(())
+        }
+    }
+
     fn read_process_mem(pid: i32, address: usize, buffer: &mut [u8]) -> Result<usize> {
         unsafe {
             let read = libc::process_vm_readv(
@@ -66,6 +249,17 @@ mod linux {
     }
 
     pub fn read_image_from_pid<'data>(pid: i32) -> Result<Image<'data>> {
+        let (image, skipped) = read_image_from_pid_tolerant(pid)?;
+        super::warn_skipped(&skipped);
+        Ok(image)
+    }
+
+    /// Read image from process skipping section pages which could not be read. Returns the image
+    /// along with the skipped address ranges, which are zero filled and excluded from scanning,
+    /// see [`Image::unreadable_ranges`]
+    pub fn read_image_from_pid_tolerant<'data>(
+        pid: i32,
+    ) -> Result<(Image<'data>, Vec<Range<usize>>)> {
         let main_module = find_main_module(pid)?;
 
         let mut image_header = vec![0; main_module.len()];
@@ -74,22 +268,32 @@ mod linux {
 
         let object = object::File::parse(image_header.as_slice())?;
 
+        // sections are read whole and then page by page if any page is unreadable, the cache
+        // avoids fetching the readable pages of such a section twice
+        let process = super::CachedReader::new(Process { pid });
+        let mut skipped = vec![];
         let mut sections = vec![];
         for section in object.sections() {
             let mut data = vec![0; section.size() as usize];
-            read_process_mem(pid, section.address() as usize, &mut data)?;
+            skipped.extend(super::read_skipping_unreadable(
+                &process,
+                section.address() as usize,
+                &mut data,
+            ));
             sections.push((section, data));
         }
 
         let memory = Memory::new_external_data(sections)?;
 
-        image::pe::PEImage::read_inner_memory::<String>(
+        let mut image = image::pe::PEImage::read_inner_memory::<String>(
             object.relative_address_base() as usize,
             None,
             false,
             memory,
             object,
-        )
+        )?;
+        image.skipped.clone_from(&skipped);
+        Ok((image, skipped))
     }
 }
 
@@ -112,13 +316,15 @@ pub use windows::*;
 
 #[cfg(windows)]
 mod windows {
+    use std::ops::Range;
+
     use anyhow::{bail, Result};
     use object::{Object, ObjectSection};
 
     use crate::image::pe::PEImage;
     use crate::{Image, Memory};
 
-    use windows::Win32::Foundation::HMODULE;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE, HMODULE};
     use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
     use windows::Win32::System::ProcessStatus::{
         EnumProcessModules, GetModuleInformation, MODULEINFO,
@@ -127,13 +333,61 @@ mod windows {
         OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
     };
 
+    /// Live process memory read via `ReadProcessMemory`. `handle` must have `PROCESS_VM_READ`
+    /// and is closed when dropped
+    pub struct Process {
+        pub handle: HANDLE,
+    }
+    impl Process {
+        pub fn open(pid: i32) -> Result<Self> {
+            Ok(Self {
+                handle: unsafe { OpenProcess(PROCESS_VM_READ, false, pid as u32)? },
+            })
+        }
+    }
+    impl Drop for Process {
+        fn drop(&mut self) {
+            unsafe {
+                let _ = CloseHandle(self.handle);
+            }
+        }
+    }
+    impl super::RawReader for Process {
+        fn read_raw(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+            unsafe {
+                ReadProcessMemory(
+                    self.handle,
+                    address as *const std::ffi::c_void,
+                    buffer.as_mut_ptr() as *mut std::ffi::c_void,
+                    buffer.len(),
+                    None,
+                )?;
+            }
+            Ok(())
+        }
+    }
+
     pub fn read_image_from_pid<'data>(pid: i32) -> Result<Image<'data>> {
-        let (memory, base) = unsafe {
-            let process = OpenProcess(
-                PROCESS_VM_READ | PROCESS_QUERY_INFORMATION,
-                false,
-                pid as u32,
-            )?;
+        let (image, skipped) = read_image_from_pid_tolerant(pid)?;
+        super::warn_skipped(&skipped);
+        Ok(image)
+    }
+
+    /// Read image from process skipping pages which could not be read. Returns the image along
+    /// with the skipped address ranges, which are zero filled and excluded from scanning, see
+    /// [`Image::unreadable_ranges`]
+    pub fn read_image_from_pid_tolerant<'data>(
+        pid: i32,
+    ) -> Result<(Image<'data>, Vec<Range<usize>>)> {
+        let (memory, base, skipped) = unsafe {
+            let reader = super::CachedReader::new(Process {
+                handle: OpenProcess(
+                    PROCESS_VM_READ | PROCESS_QUERY_INFORMATION,
+                    false,
+                    pid as u32,
+                )?,
+            });
+            let process = reader.inner().handle;
 
             let mut modules = [Default::default(); 1];
             let mut out_len = 0;
@@ -157,15 +411,10 @@ mod windows {
             )?;
 
             let mut mem = vec![0u8; info.SizeOfImage as usize];
-            ReadProcessMemory(
-                process,
-                info.lpBaseOfDll,
-                mem.as_mut_ptr() as *mut std::ffi::c_void,
-                mem.len(),
-                None,
-            )?;
+            let skipped =
+                super::read_skipping_unreadable(&reader, info.lpBaseOfDll as usize, &mut mem);
 
-            (mem, info.lpBaseOfDll as usize)
+            (mem, info.lpBaseOfDll as usize, skipped)
         };
 
         let object = object::File::parse(memory.as_slice())?;
@@ -182,6 +431,150 @@ mod windows {
 
         let memory = Memory::new_external_data(sections)?;
 
-        PEImage::read_inner_memory::<String>(base, None, false, memory, object)
+        let mut image = PEImage::read_inner_memory::<String>(base, None, false, memory, object)?;
+        image.skipped.clone_from(&skipped);
+        Ok((image, skipped))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Memory where each byte is one more than the low byte of its page index, with every page
+    /// in `bad` unreadable. Counts calls to `read_raw`
+    #[derive(Default)]
+    struct FakeReader {
+        bad: Vec<usize>,
+        reads: AtomicUsize,
+    }
+    impl RawReader for FakeReader {
+        fn read_raw(&self, address: usize, buffer: &mut [u8]) -> Result<()> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            for (i, b) in buffer.iter_mut().enumerate() {
+                let page = (address + i) / PAGE_SIZE;
+                if self.bad.contains(&page) {
+                    anyhow::bail!("page {page:#x} is unreadable");
+                }
+                *b = page as u8 + 1;
+            }
+            Ok(())
+        }
+    }
+    impl FakeReader {
+        fn reads(&self) -> usize {
+            self.reads.load(Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_cached_reader_reads() {
+        let reader = CachedReader::new(FakeReader::default());
+
+        let mut buffer = [0; 4];
+        reader.read(PAGE_SIZE * 2 - 2, &mut buffer).unwrap();
+        assert_eq!(buffer, [2, 2, 3, 3]);
+        assert_eq!(reader.inner().reads(), 2);
+
+        // both pages are now cached
+        reader.read(PAGE_SIZE + 8, &mut buffer).unwrap();
+        reader.read(PAGE_SIZE * 2 + 8, &mut buffer).unwrap();
+        assert_eq!(buffer, [3; 4]);
+        assert_eq!(reader.inner().reads(), 2);
+    }
+
+    #[test]
+    fn test_cached_reader_lru() {
+        let reader = CachedReader::with_capacity(FakeReader::default(), 2);
+        let mut buffer = [0; 1];
+
+        reader.read(0, &mut buffer).unwrap();
+        reader.read(PAGE_SIZE, &mut buffer).unwrap();
+        // touch page 0 so page 1 becomes least recently used
+        reader.read(0, &mut buffer).unwrap();
+        reader.read(PAGE_SIZE * 2, &mut buffer).unwrap();
+        assert_eq!(reader.inner().reads(), 3);
+
+        reader.read(0, &mut buffer).unwrap();
+        assert_eq!(reader.inner().reads(), 3);
+        reader.read(PAGE_SIZE, &mut buffer).unwrap();
+        assert_eq!(reader.inner().reads(), 4);
+    }
+
+    #[test]
+    fn test_cached_reader_set_capacity() {
+        let mut reader = CachedReader::new(FakeReader::default());
+        let mut buffer = [0; 1];
+        for page in 0..4 {
+            reader.read(page * PAGE_SIZE, &mut buffer).unwrap();
+        }
+        reader.set_capacity(0);
+        assert_eq!(reader.capacity(), 1);
+
+        // only the most recently used page survives
+        reader.read(3 * PAGE_SIZE, &mut buffer).unwrap();
+        assert_eq!(reader.inner().reads(), 4);
+        reader.read(2 * PAGE_SIZE, &mut buffer).unwrap();
+        assert_eq!(reader.inner().reads(), 5);
+    }
+
+    #[test]
+    fn test_cached_reader_invalidate() {
+        let reader = CachedReader::new(FakeReader::default());
+        let mut buffer = [0; 1];
+        for page in 0..4 {
+            reader.read(page * PAGE_SIZE, &mut buffer).unwrap();
+        }
+
+        // unaligned range still drops every page it overlaps
+        reader.invalidate(PAGE_SIZE + 1..PAGE_SIZE * 2 + 1);
+        for page in 0..4 {
+            reader.read(page * PAGE_SIZE, &mut buffer).unwrap();
+        }
+        assert_eq!(reader.inner().reads(), 6);
+
+        reader.invalidate(usize::MAX - 1..usize::MAX);
+        reader.invalidate_all();
+        for page in 0..4 {
+            reader.read(page * PAGE_SIZE, &mut buffer).unwrap();
+        }
+        assert_eq!(reader.inner().reads(), 10);
+    }
+
+    #[test]
+    fn test_cached_reader_errors_not_cached() {
+        let reader = CachedReader::new(FakeReader {
+            bad: vec![1],
+            ..Default::default()
+        });
+        let mut buffer = [0; 2];
+        assert!(reader.read(PAGE_SIZE - 1, &mut buffer).is_err());
+        assert!(reader.read(PAGE_SIZE - 1, &mut buffer).is_err());
+        // page 0 was cached by the first attempt, page 1 is retried each time
+        assert_eq!(reader.inner().reads(), 3);
+    }
+
+    #[test]
+    fn test_read_skipping_unreadable() {
+        let reader = CachedReader::new(FakeReader {
+            bad: vec![1, 2, 4],
+            ..Default::default()
+        });
+        let mut buffer = vec![0xff; PAGE_SIZE * 5 - 8];
+        let skipped = read_skipping_unreadable(&reader, 8, &mut buffer);
+        assert_eq!(
+            skipped,
+            [PAGE_SIZE..PAGE_SIZE * 3, PAGE_SIZE * 4..PAGE_SIZE * 5]
+        );
+        assert!(buffer[..PAGE_SIZE - 8].iter().all(|b| *b == 1));
+        assert!(buffer[PAGE_SIZE * 3 - 8..PAGE_SIZE * 4 - 8]
+            .iter()
+            .all(|b| *b == 4));
+        assert!(buffer[PAGE_SIZE - 8..PAGE_SIZE * 3 - 8]
+            .iter()
+            .all(|b| *b == 0));
+        assert!(buffer[PAGE_SIZE * 4 - 8..].iter().all(|b| *b == 0));
     }
 }
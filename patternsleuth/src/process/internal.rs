@@ -1,12 +1,214 @@
+/// Options for reading the image of the current process
+#[derive(Debug, Default, Clone)]
+pub struct ReadImageOptions {
+    /// Section kinds to load. `None` loads every section.
+    ///
+    /// Note that function lookups depend on unwind info (`.pdata` on PE, `.eh_frame` on ELF)
+    /// which is typically [`object::SectionKind::ReadOnlyData`]
+    pub section_kinds: Option<Vec<object::SectionKind>>,
+}
+impl ReadImageOptions {
+    /// Only load sections of the given kinds
+    pub fn section_kinds(mut self, kinds: impl IntoIterator<Item = object::SectionKind>) -> Self {
+        self.section_kinds = Some(kinds.into_iter().collect());
+        self
+    }
+    /// Whether sections of `kind` should be loaded
+    pub fn includes(&self, kind: object::SectionKind) -> bool {
+        self.section_kinds
+            .as_ref()
+            .map(|kinds| kinds.contains(&kind))
+            .unwrap_or(true)
+    }
+}
+
+/// Image of the current process, read on first access
+#[cfg(any(target_os = "linux", windows))]
+pub fn image() -> crate::resolvers::Result<&'static crate::Image<'static>> {
+    use std::sync::OnceLock;
+    static IMAGE: OnceLock<Result<crate::Image<'static>, String>> = OnceLock::new();
+    IMAGE
+        .get_or_init(|| read_image().map_err(|e| format!("failed to read image: {e}")))
+        .as_ref()
+        .map_err(|e| crate::resolvers::ResolveError::Msg(e.clone().into()))
+}
+
+/// Resolve `T` against the image of the current process, caching the result for the lifetime
+/// of the process
+///
+/// ```ignore
+/// let gmalloc = patternsleuth::process::internal::resolved::<GMalloc>()?;
+/// ```
+#[cfg(any(target_os = "linux", windows))]
+pub fn resolved<T: crate::resolvers::StaticResolver>() -> crate::resolvers::Result<&'static T> {
+    use std::sync::OnceLock;
+    static GLOBALS: OnceLock<crate::resolvers::ResolvedGlobals> = OnceLock::new();
+    GLOBALS.get_or_init(Default::default).get(image()?)
+}
+
+/// Ranges of the sections of `image`, read from the current process, whose pages are not
+/// readable. Page protections are queried once here rather than on every scan
+#[cfg(any(target_os = "linux", windows))]
+fn unreadable_ranges(image: &crate::Image<'_>) -> Vec<std::ops::Range<usize>> {
+    let mut skipped = vec![];
+    for section in image.memory.sections() {
+        let start = section.data().as_ptr() as usize;
+        let end = start + section.len();
+        let address = |ptr: usize| section.address() + (ptr - start);
+        let readable = readable_ranges(start..end).unwrap_or_else(|err| {
+            tracing::warn!(
+                "failed to query readable ranges of {start:x?}..{end:x?}, skipping: {err:#}"
+            );
+            vec![]
+        });
+        let mut offset = start;
+        for range in readable {
+            if range.start > offset {
+                skipped.push(address(offset)..address(range.start));
+            }
+            offset = range.end;
+        }
+        if end > offset {
+            skipped.push(address(offset)..address(end));
+        }
+    }
+    skipped
+}
+
+/// Mapped region of the current process' address space
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedRegion {
+    pub range: std::ops::Range<usize>,
+    /// Platform specific protection flags. Regions are considered changed if these differ
+    pub protection: u32,
+    pub readable: bool,
+}
+
+/// Point-in-time record of the memory mappings of the current process, see [`changed_sections`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub regions: Vec<MappedRegion>,
+}
+#[cfg(any(target_os = "linux", windows))]
+impl Snapshot {
+    pub fn capture() -> anyhow::Result<Self> {
+        Ok(Self {
+            regions: mapped_regions()?,
+        })
+    }
+}
+
+/// Readable regions of the current process that were mapped or had their protection changed
+/// since `since` was captured, e.g. code loaded by DLC or hot reload. Capture a new
+/// [`Snapshot`] afterwards to continue incrementally
+#[cfg(any(target_os = "linux", windows))]
+pub fn changed_sections(since: &Snapshot) -> anyhow::Result<Vec<std::ops::Range<usize>>> {
+    Ok(mapped_regions()?
+        .into_iter()
+        .filter(|region| region.readable && !since.regions.contains(region))
+        .map(|region| region.range)
+        .collect())
+}
+
+/// Scan only the regions returned by [`changed_sections`]. Returns sorted addresses for each
+/// pattern.
+///
+/// # Safety
+/// Regions must not be unmapped by other threads while being scanned
+#[cfg(any(target_os = "linux", windows))]
+pub unsafe fn scan_changed_sections(
+    since: &Snapshot,
+    patterns: &[&patternsleuth_scanner::Pattern],
+) -> anyhow::Result<Vec<Vec<usize>>> {
+    let mut results = vec![vec![]; patterns.len()];
+    for range in changed_sections(since)? {
+        let data = std::slice::from_raw_parts(range.start as *const u8, range.len());
+        let res = patternsleuth_scanner::scan_pattern(patterns, range.start, data);
+        for (all, found) in results.iter_mut().zip(res) {
+            all.extend(found);
+        }
+    }
+    for res in &mut results {
+        res.sort();
+    }
+    Ok(results)
+}
+
+/// Bytes written to a live image by [`crate::Image::patch`]. The original bytes are restored
+/// when the guard is dropped
+#[cfg(any(target_os = "linux", windows))]
+#[must_use = "the patch is reverted when the guard is dropped"]
+pub struct PatchGuard {
+    address: usize,
+    original: Vec<u8>,
+    kind: object::SectionKind,
+}
+#[cfg(any(target_os = "linux", windows))]
+impl PatchGuard {
+    /// Address the patch was applied at
+    pub fn address(&self) -> usize {
+        self.address
+    }
+    /// Bytes that were overwritten by the patch
+    pub fn original(&self) -> &[u8] {
+        &self.original
+    }
+    /// Keep the patch applied permanently
+    pub fn leak(self) {
+        std::mem::forget(self)
+    }
+}
+#[cfg(any(target_os = "linux", windows))]
+impl Drop for PatchGuard {
+    fn drop(&mut self) {
+        if let Err(err) = unsafe { write_protected(self.address, &self.original, self.kind) } {
+            tracing::error!("failed to revert patch at {:#x}: {err}", self.address);
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", windows))]
+impl crate::Image<'_> {
+    /// Overwrite `bytes` at `address` of the image of the current process, temporarily making
+    /// the page writable and flushing the instruction cache. Returns a guard that restores the
+    /// original bytes on drop. Fails for images that are not mapped in the current process (e.g.
+    /// read from disk or another process) and for patches spanning multiple sections.
+    ///
+    /// # Safety
+    /// Other threads may be executing or reading the patched bytes
+    pub unsafe fn patch(&self, address: usize, bytes: &[u8]) -> anyhow::Result<PatchGuard> {
+        let section = self.memory.get_section_containing(address)?;
+        if section.data().as_ptr() as usize != section.address() {
+            anyhow::bail!("image is not loaded in the current process");
+        }
+        let offset = address - section.address();
+        let original = section
+            .data()
+            .get(offset..offset + bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("patch extends past end of section"))?
+            .to_vec();
+        write_protected(address, bytes, section.kind())?;
+        Ok(PatchGuard {
+            address,
+            original,
+            kind: section.kind(),
+        })
+    }
+}
+
 #[cfg(target_os = "linux")]
 pub use linux::*;
 
 #[cfg(target_os = "linux")]
 mod linux {
-    use std::ptr::{null, null_mut};
+    use std::{
+        ops::Range,
+        ptr::{null, null_mut},
+    };
 
-    use anyhow::Result;
+    use anyhow::{bail, Context, Result};
 
+    use super::ReadImageOptions;
     use crate::Image;
     use libc::{dl_iterate_phdr, Elf64_Addr, Elf64_Phdr, Elf64_Sxword, Elf64_Xword, PT_LOAD};
 
@@ -52,6 +254,89 @@ mod linux {
     }
 
     pub fn read_image<'data>() -> Result<Image<'data>> {
+        read_image_with_options(&Default::default())
+    }
+
+    /// Split `range` of the current process' address space into sub-ranges that are mapped
+    /// readable according to `/proc/self/maps`
+    pub fn readable_ranges(range: Range<usize>) -> Result<Vec<Range<usize>>> {
+        let maps = std::fs::read_to_string("/proc/self/maps")?;
+        let mut ranges = vec![];
+        for line in maps.lines() {
+            let mut split = line.split_whitespace();
+            let (Some(map), Some(permissions)) = (split.next(), split.next()) else {
+                bail!("failed to parse line of maps: {line:?}");
+            };
+            if !permissions.starts_with('r') {
+                continue;
+            }
+            let (start, end) = map
+                .split_once('-')
+                .with_context(|| format!("failed to parse map range: {map:?}"))?;
+            let start = usize::from_str_radix(start, 16)?.max(range.start);
+            let end = usize::from_str_radix(end, 16)?.min(range.end);
+            if start < end {
+                ranges.push(start..end);
+            }
+        }
+        Ok(ranges)
+    }
+
+    /// All regions mapped in the current process according to `/proc/self/maps`
+    pub(super) fn mapped_regions() -> Result<Vec<super::MappedRegion>> {
+        let maps = std::fs::read_to_string("/proc/self/maps")?;
+        let mut regions = vec![];
+        for line in maps.lines() {
+            let mut split = line.split_whitespace();
+            let (Some(map), Some(permissions)) = (split.next(), split.next()) else {
+                bail!("failed to parse line of maps: {line:?}");
+            };
+            let (start, end) = map
+                .split_once('-')
+                .with_context(|| format!("failed to parse map range: {map:?}"))?;
+            let protection = permissions
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !matches!(c, '-' | 'p'))
+                .fold(0, |acc, (i, _)| acc | 1 << i);
+            regions.push(super::MappedRegion {
+                range: usize::from_str_radix(start, 16)?..usize::from_str_radix(end, 16)?,
+                protection,
+                readable: permissions.starts_with('r'),
+            });
+        }
+        Ok(regions)
+    }
+
+    /// Copy `bytes` to `address`, making the containing pages writable for the duration of the
+    /// write. Protection is restored based on `kind` since the previous protection is not known.
+    /// x86 keeps the instruction cache coherent so no explicit flush is required
+    pub(crate) unsafe fn write_protected(
+        address: usize,
+        bytes: &[u8],
+        kind: object::SectionKind,
+    ) -> Result<()> {
+        let page = 0x1000;
+        let start = address & !(page - 1);
+        let len = (address + bytes.len()).next_multiple_of(page) - start;
+        let protect = |prot| {
+            if libc::mprotect(start as *mut libc::c_void, len, prot) != 0 {
+                bail!("mprotect failed: {}", std::io::Error::last_os_error());
+            }
+            Ok(())
+        };
+        protect(libc::PROT_READ | libc::PROT_WRITE | libc::PROT_EXEC)?;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), address as *mut u8, bytes.len());
+        protect(match kind {
+            object::SectionKind::Text => libc::PROT_READ | libc::PROT_EXEC,
+            object::SectionKind::Data | object::SectionKind::UninitializedData => {
+                libc::PROT_READ | libc::PROT_WRITE
+            }
+            _ => libc::PROT_READ,
+        })
+    }
+
+    pub fn read_image_with_options<'data>(options: &ReadImageOptions) -> Result<Image<'data>> {
         unsafe {
             let mut info = libc::dl_phdr_info {
                 dlpi_addr: 0,
@@ -104,7 +389,14 @@ mod linux {
             #[cfg(not(feature = "symbols"))]
             let exe_path: Option<std::path::PathBuf> = None;
             //eprintln!("Reading image internal");
-            Image::read(Some(base_addr), data, exe_path, false)
+            let mut image = Image::read(Some(base_addr), data, exe_path, false)?;
+            // unwind info must be parsed from the full mapping so filter afterwards
+            image
+                .memory
+                .sections
+                .retain(|section| options.includes(section.kind()));
+            image.skipped = super::unreadable_ranges(&image);
+            Ok(image)
         }
     }
 }
@@ -114,18 +406,106 @@ pub use windows::*;
 
 #[cfg(windows)]
 mod windows {
+    use std::ops::Range;
+
     use anyhow::{Context, Result};
     use object::{Object, ObjectSection};
     use windows::Win32::System::{
+        Diagnostics::Debug::FlushInstructionCache,
         LibraryLoader::GetModuleHandleA,
+        Memory::{
+            VirtualProtect, VirtualQuery, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_EXECUTE,
+            PAGE_EXECUTE_READWRITE, PAGE_GUARD, PAGE_NOACCESS, PAGE_PROTECTION_FLAGS,
+        },
         ProcessStatus::{GetModuleInformation, MODULEINFO},
         Threading::GetCurrentProcess,
     };
 
+    use super::ReadImageOptions;
     use crate::image::pe::PEImage;
     use crate::{Image, Memory};
 
     pub fn read_image<'data>() -> Result<Image<'data>> {
+        read_image_with_options(&Default::default())
+    }
+
+    /// Split `range` of the current process' address space into sub-ranges that are committed
+    /// and readable according to `VirtualQuery`
+    pub fn readable_ranges(range: Range<usize>) -> Result<Vec<Range<usize>>> {
+        let mut ranges = vec![];
+        let mut address = range.start;
+        while address < range.end {
+            let mut info = MEMORY_BASIC_INFORMATION::default();
+            let len = unsafe {
+                VirtualQuery(
+                    Some(address as *const std::ffi::c_void),
+                    &mut info,
+                    std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+                )
+            };
+            if len == 0 {
+                break;
+            }
+            let end = (info.BaseAddress as usize + info.RegionSize).min(range.end);
+            let unreadable = PAGE_NOACCESS.0 | PAGE_GUARD.0 | PAGE_EXECUTE.0;
+            if info.State == MEM_COMMIT && info.Protect.0 & unreadable == 0 {
+                ranges.push(address..end);
+            }
+            address = end;
+        }
+        Ok(ranges)
+    }
+
+    /// All committed regions of the current process according to `VirtualQuery`
+    pub(super) fn mapped_regions() -> Result<Vec<super::MappedRegion>> {
+        let mut regions = vec![];
+        let mut address = 0;
+        loop {
+            let mut info = MEMORY_BASIC_INFORMATION::default();
+            let len = unsafe {
+                VirtualQuery(
+                    Some(address as *const std::ffi::c_void),
+                    &mut info,
+                    std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+                )
+            };
+            if len == 0 {
+                break;
+            }
+            let start = info.BaseAddress as usize;
+            let Some(end) = start.checked_add(info.RegionSize) else {
+                break;
+            };
+            if info.State == MEM_COMMIT {
+                let unreadable = PAGE_NOACCESS.0 | PAGE_GUARD.0 | PAGE_EXECUTE.0;
+                regions.push(super::MappedRegion {
+                    range: start..end,
+                    protection: info.Protect.0,
+                    readable: info.Protect.0 & unreadable == 0,
+                });
+            }
+            address = end;
+        }
+        Ok(regions)
+    }
+
+    /// Copy `bytes` to `address`, making the containing pages writable for the duration of the
+    /// write and flushing the instruction cache afterwards
+    pub(crate) unsafe fn write_protected(
+        address: usize,
+        bytes: &[u8],
+        _kind: object::SectionKind,
+    ) -> Result<()> {
+        let ptr = address as *const std::ffi::c_void;
+        let mut old = PAGE_PROTECTION_FLAGS::default();
+        VirtualProtect(ptr, bytes.len(), PAGE_EXECUTE_READWRITE, &mut old)?;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), address as *mut u8, bytes.len());
+        VirtualProtect(ptr, bytes.len(), old, &mut old)?;
+        FlushInstructionCache(GetCurrentProcess(), Some(ptr), bytes.len())?;
+        Ok(())
+    }
+
+    pub fn read_image_with_options<'data>(options: &ReadImageOptions) -> Result<Image<'data>> {
         let main_module =
             unsafe { GetModuleHandleA(None) }.context("could not find main module")?;
         let process = unsafe { GetCurrentProcess() };
@@ -152,7 +532,10 @@ mod windows {
         let image_base_address = object.relative_address_base() as usize;
 
         let mut sections = vec![];
-        for section in object.sections() {
+        for section in object
+            .sections()
+            .filter(|section| options.includes(section.kind()))
+        {
             let addr = section.address() as usize - image_base_address;
             let size = section.size() as usize;
             sections.push((section, &memory[addr..addr + size]));
@@ -160,6 +543,9 @@ mod windows {
 
         let memory = Memory::new_internal_data(sections)?;
 
-        PEImage::read_inner_memory::<String>(image_base_address, None, false, memory, object)
+        let mut image =
+            PEImage::read_inner_memory::<String>(image_base_address, None, false, memory, object)?;
+        image.skipped = super::unreadable_ranges(&image);
+        Ok(image)
     }
 }
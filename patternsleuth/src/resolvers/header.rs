@@ -0,0 +1,44 @@
+//! C++ header generation from resolver results.
+//!
+//! Bridges runtime resolution into compile-time C++ usage: resolved singletons and the entries of
+//! name to address maps become `#define`s of their offset from the image base, named after the
+//! resolver, so mods can include the generated header and add the runtime module base
+//! themselves.
+
+use std::fmt::Write;
+
+use super::snapshot::{Snapshot, SnapshotEntry};
+
+/// Prefix applied to every generated `#define` to avoid collisions with engine macros
+pub const DEFINE_PREFIX: &str = "PATTERNSLEUTH_";
+
+/// Generate a C++ header from `snapshot`. Singleton results are emitted as `#define`s of their
+/// image relative offset, map results as one `#define` per entry suffixed with its name. Other
+/// values and failed resolvers are recorded as comments so the header documents what is missing
+pub fn generate_offset_header(snapshot: &Snapshot) -> String {
+    let mut out = String::new();
+    writeln!(out, "#pragma once").unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "// Generated by patternsleuth. Offsets are relative to the image base"
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+
+    for (name, entry) in &snapshot.entries {
+        match entry {
+            SnapshotEntry::Rva(rva) => {
+                writeln!(out, "#define {DEFINE_PREFIX}{name} 0x{rva:x}").unwrap()
+            }
+            SnapshotEntry::Map(map) => {
+                for (key, rva) in map {
+                    writeln!(out, "#define {DEFINE_PREFIX}{name}_{key} 0x{rva:x}").unwrap()
+                }
+            }
+            SnapshotEntry::Value(value) => writeln!(out, "// {name} = {value}").unwrap(),
+            SnapshotEntry::Error(err) => writeln!(out, "// {name} unresolved: {err}").unwrap(),
+        }
+    }
+    out
+}
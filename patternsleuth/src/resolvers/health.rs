@@ -0,0 +1,120 @@
+//! Signature quality checks against a reference binary.
+//!
+//! Resolvers are typically written against one or two games and silently degrade as patterns
+//! accumulate. Running [`assert_signature_health`] in a test against a checked-in sample turns
+//! "are the signatures still selective?" into an enforced invariant:
+//!
+//! ```ignore
+//! #[test]
+//! fn gmalloc_health() {
+//!     let data = std::fs::read("samples/game.exe").unwrap();
+//!     let image = Image::read::<&str>(None, &data, None, false).unwrap();
+//!     assert_signature_health(&image, GMalloc::resolver());
+//! }
+//! ```
+
+use std::{collections::HashSet, fmt::Display};
+
+use patternsleuth_scanner::Pattern;
+
+use crate::Image;
+
+use super::{eval, ResolverFactory, Result, ScanRecord};
+
+/// Minimum [`Pattern::literal_len`] accepted by [`assert_signature_health`]
+pub const MIN_LITERAL_LEN: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureIssueKind {
+    /// Pattern without xrefs matched more than once. Patterns with xrefs locate references and
+    /// are expected to match at every reference site so are exempt
+    NotUnique { matches: usize },
+    /// Pattern has fewer than the required number of literal bytes
+    TooShort { literal_len: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct SignatureIssue {
+    /// Resolver which scanned the pattern
+    pub resolver: &'static str,
+    pub pattern: Pattern,
+    pub kind: SignatureIssueKind,
+}
+impl Display for SignatureIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {:?} ", self.resolver, self.pattern)?;
+        match self.kind {
+            SignatureIssueKind::NotUnique { matches } => write!(f, "matched {matches} times"),
+            SignatureIssueKind::TooShort { literal_len } => {
+                write!(f, "has only {literal_len} literal bytes")
+            }
+        }
+    }
+}
+
+/// Resolve `resolver` against `image` and check every pattern scanned along the way, including
+/// those of its dependencies. Fails if the resolver itself fails
+pub fn signature_health<T: Send + Sync>(
+    image: &Image<'_>,
+    resolver: &'static ResolverFactory<T>,
+    min_literal_len: usize,
+) -> Result<Vec<SignatureIssue>> {
+    let (res, mut scans) = eval(image, |ctx| {
+        Box::pin(async {
+            ctx.read.write.lock().unwrap().scans = Some(vec![]);
+            let res = ctx.resolve(resolver).await.map(|_| ());
+            let scans = ctx.read.write.lock().unwrap().scans.take();
+            (res, scans.unwrap_or_default())
+        })
+    });
+    res?;
+
+    // dependencies are only resolved once but identical patterns may be queued repeatedly
+    let mut seen = HashSet::new();
+    scans.retain(|s| seen.insert((s.resolver, s.pattern.clone())));
+
+    let mut issues = vec![];
+    for ScanRecord {
+        resolver,
+        pattern,
+        matches,
+    } in scans
+    {
+        let literal_len = pattern.literal_len();
+        if literal_len < min_literal_len {
+            issues.push(SignatureIssue {
+                resolver,
+                pattern: pattern.clone(),
+                kind: SignatureIssueKind::TooShort { literal_len },
+            });
+        }
+        if pattern.xrefs.is_empty() && matches > 1 {
+            issues.push(SignatureIssue {
+                resolver,
+                pattern,
+                kind: SignatureIssueKind::NotUnique { matches },
+            });
+        }
+    }
+    Ok(issues)
+}
+
+/// Panic listing every issue found by [`signature_health`] with [`MIN_LITERAL_LEN`]. Intended
+/// to be called from tests against a reference binary
+pub fn assert_signature_health<T: Send + Sync>(
+    image: &Image<'_>,
+    resolver: &'static ResolverFactory<T>,
+) {
+    match signature_health(image, resolver, MIN_LITERAL_LEN) {
+        Ok(issues) if issues.is_empty() => {}
+        Ok(issues) => {
+            let issues = issues
+                .iter()
+                .map(|i| format!("  {i}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!("unhealthy signatures:\n{issues}");
+        }
+        Err(err) => panic!("resolver failed: {err}"),
+    }
+}
@@ -1,6 +1,10 @@
+pub mod header;
+pub mod health;
+pub mod snapshot;
+pub mod trace;
 pub mod unreal;
 
-use crate::{Image, MemoryAccessError};
+use crate::{image::BuildId, FromLeBytes, Image, MemoryAccessError, MemoryAccessorTrait};
 use futures::{
     channel::oneshot,
     executor::LocalPool,
@@ -14,12 +18,15 @@ use patternsleuth_scanner::Pattern;
 use std::{
     any::{Any, TypeId},
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
-    sync::{Arc, Mutex},
+    ops::Range,
+    sync::{Arc, Mutex, OnceLock},
 };
 
-/// Given an iterator of values, returns Ok(value) if all values are equal or Err
+/// Given an iterator of values, returns Ok(value) if all values are equal or Err. Duplicates
+/// (e.g. from redundant alternative patterns matching the same address) collapse into one so
+/// only distinct values are reported as ambiguous
 pub fn ensure_one<T: std::fmt::Debug + PartialEq>(data: impl IntoIterator<Item = T>) -> Result<T> {
     try_ensure_one(data.into_iter().map(|v| Ok(v)))
 }
@@ -55,6 +62,46 @@ pub fn try_ensure_one<T: std::fmt::Debug + PartialEq>(
     }
 }
 
+/// Like [`ensure_one`] but if there are multiple unique values a warning is logged and the
+/// first is returned instead of failing. Intended for exploratory work on new games, resolvers
+/// should stay strict
+pub fn ensure_one_lenient<T: std::fmt::Debug + PartialEq>(
+    data: impl IntoIterator<Item = T>,
+) -> Result<T> {
+    let mut unique = vec![];
+    for value in data {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    if unique.len() > 1 {
+        tracing::warn!(
+            "found {} unique values {unique:X?}, using first",
+            unique.len()
+        );
+    }
+    if unique.is_empty() {
+        return Err(ResolveError::Msg("expected at least one value".into()));
+    }
+    Ok(unique.swap_remove(0))
+}
+
+/// Cross-check two independent sets of results. Each must [`ensure_one`] on its own and both
+/// must agree, catching silent misresolution when one signature drifts onto the wrong target
+pub fn ensure_agree<T: std::fmt::Debug + PartialEq>(
+    a: impl IntoIterator<Item = T>,
+    b: impl IntoIterator<Item = T>,
+) -> Result<T> {
+    let a = ensure_one(a)?;
+    let b = ensure_one(b)?;
+    if a != b {
+        return Err(ResolveError::Msg(
+            format!("independent results disagree: {a:X?} != {b:X?}").into(),
+        ));
+    }
+    Ok(a)
+}
+
 pub type Result<T> = std::result::Result<T, ResolveError>;
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(
@@ -106,6 +153,9 @@ impl<T> Context<T> for Option<T> {
 
 pub struct NamedResolver {
     pub name: &'static str,
+    /// Whether the resolver was defined with `impl_resolver_singleton!` and resolves to a single
+    /// address
+    pub singleton: bool,
     pub getter: fn() -> &'static DynResolverFactory,
 }
 
@@ -118,7 +168,10 @@ type DynResolver<'ctx> = BoxFuture<'ctx, Result<Arc<dyn Resolution>>>;
 type Resolver<'ctx, T> = BoxFuture<'ctx, Result<T>>;
 
 #[cfg_attr(feature = "serde-resolvers", typetag::serde(tag = "type"))]
-pub trait Resolution: std::fmt::Debug + std::any::Any + Send + Sync + Singleton + DynEq {}
+pub trait Resolution:
+    std::fmt::Debug + std::any::Any + Send + Sync + Singleton + SnapshotValue + DynEq
+{
+}
 
 /// Allow comparison of dyn Resolution
 /// <https://users.rust-lang.org/t/how-to-compare-two-trait-objects-for-equality/88063/3>
@@ -175,6 +228,73 @@ pub struct ResolverFactory<T> {
     pub factory: for<'ctx> fn(&'ctx AsyncContext<'_>) -> Resolver<'ctx, T>,
 }
 
+/// Implemented by every resolver to allow looking up its factory generically
+pub trait StaticResolver: Sized + Send + Sync + 'static {
+    fn resolver() -> &'static ResolverFactory<Self>;
+}
+
+/// Lazily resolved result suitable for storing in a `static`, replacing `static mut` globals
+pub struct ResolvedGlobal<T> {
+    cell: OnceLock<Result<T>>,
+}
+impl<T> Default for ResolvedGlobal<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> ResolvedGlobal<T> {
+    pub const fn new() -> Self {
+        Self {
+            cell: OnceLock::new(),
+        }
+    }
+    /// Return the result if it has already been resolved
+    pub fn get(&self) -> Option<&Result<T>> {
+        self.cell.get()
+    }
+}
+impl<T: Send + Sync> ResolvedGlobal<T> {
+    /// Return the cached result, resolving it against `image` on first access. Concurrent
+    /// callers block until the first resolution completes
+    pub fn get_or_resolve(
+        &self,
+        image: &Image<'_>,
+        resolver: &'static ResolverFactory<T>,
+    ) -> &Result<T> {
+        self.cell.get_or_init(|| resolve(image, resolver))
+    }
+}
+impl<T: StaticResolver> ResolvedGlobal<T> {
+    /// Like [`Self::get_or_resolve`] but using the default resolver for `T`
+    pub fn get_or_init(&self, image: &Image<'_>) -> &Result<T> {
+        self.get_or_resolve(image, T::resolver())
+    }
+}
+
+/// Process-wide cache of [`ResolvedGlobal`]s keyed by resolver type
+#[derive(Default)]
+pub struct ResolvedGlobals {
+    globals: Mutex<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>,
+}
+impl ResolvedGlobals {
+    /// Return the cached result for `T`, resolving it against `image` on first access
+    pub fn get<T: StaticResolver>(&self, image: &Image<'_>) -> Result<&T> {
+        let global = {
+            let mut lock = self.globals.lock().unwrap();
+            *lock.entry(TypeId::of::<T>()).or_insert_with(|| {
+                let global: &'static ResolvedGlobal<T> = Box::leak(Box::default());
+                global as &'static (dyn Any + Send + Sync)
+            })
+        };
+        global
+            .downcast_ref::<ResolvedGlobal<T>>()
+            .unwrap()
+            .get_or_init(image)
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+}
+
 pub use ::futures;
 pub use ::inventory;
 #[cfg(feature = "serde-resolvers")]
@@ -189,13 +309,15 @@ macro_rules! _matcharm_generator {
             let img = &$ctx.image().image_type;
             let mut res = None;
             $(
-                $crate::resolvers::cfg_image::$img_ident! {
+                $crate::resolvers::cfg_collect::$img_ident! {
                     if matches!(img, $crate::image::$enum_name_it::$img_ident(_)) {
                         res = Some($name::$img_ident($ctx).await);
                     }
                 }
             )*
-            res.unwrap()
+            res.unwrap_or_else(|| Err($crate::resolvers::ResolveError::Msg(
+                "resolver not implemented for image type".into(),
+            )))
         }
     }
 }
@@ -216,11 +338,23 @@ macro_rules! _cfg_image_elf { ($($args:tt)*) => { $($args)* } }
 macro_rules! _cfg_image_elf {
     ($($args:tt)*) => {};
 }
+#[cfg(feature = "image-macho")]
+#[macro_export]
+macro_rules! _cfg_image_macho { ($($args:tt)*) => { $($args)* } }
+#[cfg(not(feature = "image-macho"))]
+#[macro_export]
+macro_rules! _cfg_image_macho {
+    ($($args:tt)*) => {};
+}
+#[macro_export]
+macro_rules! _cfg_never {
+    ($($args:tt)*) => {};
+}
 
 #[macro_export]
 macro_rules! _impl_resolver {
     (all, $name:ident, |$ctx:ident| async $x:block ) => {
-        $crate::_impl_resolver_inner!($name, |$ctx| async $x);
+        $crate::_impl_resolver_inner!($name, false, |$ctx| async $x);
 
         impl $crate::resolvers::Singleton for $name {
             fn get(&self) -> Option<usize> {
@@ -239,7 +373,7 @@ macro_rules! _impl_resolver {
     };
 
     (collect, $name:ident) => {
-        $crate::_impl_resolver_inner!($name, |ctx| async {
+        $crate::_impl_resolver_inner!($name, false, |ctx| async {
             $crate::image::image_type_reflection!(all, impl_resolver; generate; {ctx, $name})
         });
 
@@ -262,11 +396,14 @@ macro_rules! _impl_resolver {
 
 #[macro_export]
 macro_rules! _impl_resolver_singleton {
-    (all, $name:ident, |$ctx:ident| async $x:block ) => {
-        $crate::_impl_resolver_inner!($name, |$ctx| async {
+    (all, $name:ident $(, verify = $verify:literal)?, |$ctx:ident| async $x:block ) => {
+        $crate::_impl_resolver_inner!($name, true, |$ctx| async {
             if let Some(a) = std::env::var(concat!("PATTERNSLEUTH_RES_", stringify!($name))).ok().and_then(|s| (s.strip_prefix("0x").map(|s| usize::from_str_radix(s, 16).ok()).unwrap_or_else(|| s.parse().ok()))) {
                 return Ok($name(a));
             }
+            if let Some(a) = $ctx.pre_resolved(stringify!($name), None $(.or(Some($verify)))?) {
+                return Ok($name(a));
+            }
             $x
         });
 
@@ -275,6 +412,12 @@ macro_rules! _impl_resolver_singleton {
                 Some(self.0)
             }
         }
+
+        impl $crate::resolvers::SnapshotValue for $name {
+            fn snapshot(&self, base: usize) -> $crate::resolvers::snapshot::SnapshotEntry {
+                $crate::resolvers::snapshot::SnapshotEntry::Rva(self.0.wrapping_sub(base))
+            }
+        }
     };
 
     ($arch:ident, $name:ident, |$ctx:ident| async $x:block ) => {
@@ -286,11 +429,14 @@ macro_rules! _impl_resolver_singleton {
         }
     };
 
-    (collect, $name:ident) => {
-        $crate::_impl_resolver_inner!($name, |ctx| async {
+    (collect, $name:ident $(, verify = $verify:literal)?) => {
+        $crate::_impl_resolver_inner!($name, true, |ctx| async {
             if let Some(a) = std::env::var(concat!("PATTERNSLEUTH_RES_", stringify!($name))).ok().and_then(|s| (s.strip_prefix("0x").map(|s| usize::from_str_radix(s, 16).ok()).unwrap_or_else(|| s.parse().ok()))) {
                 return Ok($name(a));
             }
+            if let Some(a) = ctx.pre_resolved(stringify!($name), None $(.or(Some($verify)))?) {
+                return Ok($name(a));
+            }
             $crate::image::image_type_reflection!(all, impl_resolver_singleton; generate; {ctx, $name})
         });
 
@@ -300,6 +446,12 @@ macro_rules! _impl_resolver_singleton {
             }
         }
 
+        impl $crate::resolvers::SnapshotValue for $name {
+            fn snapshot(&self, base: usize) -> $crate::resolvers::snapshot::SnapshotEntry {
+                $crate::resolvers::snapshot::SnapshotEntry::Rva(self.0.wrapping_sub(base))
+            }
+        }
+
         impl $crate::resolvers::PleaseAddCollectForMe for $name {}
     };
 
@@ -312,14 +464,20 @@ macro_rules! _impl_resolver_singleton {
 }
 #[macro_export]
 macro_rules! _impl_resolver_inner {
-    ( $name:ident, |$ctx:ident| async $x:block ) => {
+    ( $name:ident, $singleton:literal, |$ctx:ident| async $x:block ) => {
         $crate::resolvers::inventory::submit! {
-            $crate::resolvers::NamedResolver { name: stringify!($name), getter: $name::dyn_resolver }
+            $crate::resolvers::NamedResolver { name: stringify!($name), singleton: $singleton, getter: $name::dyn_resolver }
         }
 
         #[cfg_attr(feature = "serde-resolvers", $crate::resolvers::typetag::serde)]
         impl $crate::resolvers::Resolution for $name {}
 
+        impl $crate::resolvers::StaticResolver for $name {
+            fn resolver() -> &'static $crate::resolvers::ResolverFactory<$name> {
+                $name::resolver()
+            }
+        }
+
         impl $name {
             pub fn resolver() -> &'static $crate::resolvers::ResolverFactory<$name> {
                 static GLOBAL: ::std::sync::OnceLock<&$crate::resolvers::ResolverFactory<$name>> = ::std::sync::OnceLock::new();
@@ -345,36 +503,151 @@ macro_rules! _impl_resolver_inner {
     };
 }
 
+/// Define a struct of resolvers which resolves only if every member resolves.
+///
+/// By default all members are resolved concurrently. Prefixing the definition with `@in_order`
+/// instead resolves members one at a time in declaration order, returning as soon as any
+/// required member fails. Resolvers carry no cost estimate so members are never reordered, the
+/// caller must declare cheap and highly selective resolvers first so that scanning the wrong
+/// binary fails quickly rather than paying for every member.
+///
+/// Members whose first attribute is `#[optional]` are stored as `Option<Arc<T>>` and do not fail
+/// the collector if they fail to resolve, allowing one collector to be used across games where
+/// some functions are absent.
 #[macro_export]
 macro_rules! _impl_try_collector {
     (
+        @in_order
         $(#[$outer:meta])*
         $struct_vis:vis struct $struct_name:ident {
-            $(
-                $(#[$inner:ident $($args:tt)*])*
-                $member_vis:vis $member_name:ident: $resolver:path,
-            )*
+            $($body:tt)*
         }
     ) => {
-        #[allow(non_snake_case)]
-        $(#[$outer])*
-        $struct_vis struct $struct_name {
-            $(
+        $crate::_impl_try_collector_munch!(
+            @munch in_order { $(#[$outer])* } [$struct_vis] $struct_name {} [] { $($body)* }
+        );
+    };
+    (
+        $(#[$outer:meta])*
+        $struct_vis:vis struct $struct_name:ident {
+            $($body:tt)*
+        }
+    ) => {
+        $crate::_impl_try_collector_munch!(
+            @munch concurrent { $(#[$outer])* } [$struct_vis] $struct_name {} [] { $($body)* }
+        );
+    };
+}
+
+/// Implementation detail of [`impl_try_collector`]. Sorts members into required and optional
+/// one at a time, accumulating the struct fields and members to resolve
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impl_try_collector_munch {
+    (
+        @munch $mode:ident $outer:tt $vis:tt $struct_name:ident { $($fields:tt)* } [ $($members:tt)* ] {
+            #[optional]
+            $(#[$inner:ident $($args:tt)*])*
+            $member_vis:vis $member_name:ident: $resolver:path,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::_impl_try_collector_munch!(
+            @munch $mode $outer $vis $struct_name {
+                $($fields)*
+                $(#[$inner $($args)*])*
+                $member_vis $member_name: ::std::option::Option<::std::sync::Arc<$resolver>>,
+            } [ $($members)* (optional $member_name $resolver) ] { $($rest)* }
+        );
+    };
+    (
+        @munch $mode:ident $outer:tt $vis:tt $struct_name:ident { $($fields:tt)* } [ $($members:tt)* ] {
+            $(#[$inner:ident $($args:tt)*])*
+            $member_vis:vis $member_name:ident: $resolver:path,
+            $($rest:tt)*
+        }
+    ) => {
+        $crate::_impl_try_collector_munch!(
+            @munch $mode $outer $vis $struct_name {
+                $($fields)*
                 $(#[$inner $($args)*])*
                 $member_vis $member_name: ::std::sync::Arc<$resolver>,
+            } [ $($members)* (required $member_name $resolver) ] { $($rest)* }
+        );
+    };
+    (
+        @munch in_order { $($outer:tt)* } [ $($struct_vis:tt)* ] $struct_name:ident { $($fields:tt)* }
+        [ $( ($kind:ident $member_name:ident $resolver:path) )* ] {}
+    ) => {
+        #[allow(non_snake_case)]
+        $($outer)*
+        $($struct_vis)* struct $struct_name {
+            $($fields)*
+        }
+        $crate::_impl_resolver!(all, $struct_name, |ctx| async {
+            $(
+                #[allow(non_snake_case)]
+                let $member_name = $crate::_impl_try_collector_member!($kind, ctx, $resolver).await?;
             )*
+            Ok($struct_name {
+                $( $member_name, )*
+            })
+        });
+
+        impl $crate::resolvers::SnapshotValue for $struct_name {
+            fn snapshot(&self, base: usize) -> $crate::resolvers::snapshot::SnapshotEntry {
+                $crate::resolvers::snapshot::SnapshotEntry::members([
+                    $( (
+                        stringify!($member_name),
+                        $crate::resolvers::snapshot::SnapshotMember::snapshot_member(&self.$member_name, base),
+                    ), )*
+                ])
+            }
+        }
+    };
+    (
+        @munch concurrent { $($outer:tt)* } [ $($struct_vis:tt)* ] $struct_name:ident { $($fields:tt)* }
+        [ $( ($kind:ident $member_name:ident $resolver:path) )* ] {}
+    ) => {
+        #[allow(non_snake_case)]
+        $($outer)*
+        $($struct_vis)* struct $struct_name {
+            $($fields)*
         }
         $crate::_impl_resolver!(all, $struct_name, |ctx| async {
             #[allow(non_snake_case)]
             let (
                 $( $member_name, )*
             ) = $crate::resolvers::futures::try_join!(
-                $( ctx.resolve($resolver::resolver()), )*
+                $( $crate::_impl_try_collector_member!($kind, ctx, $resolver), )*
             )?;
             Ok($struct_name {
                 $( $member_name, )*
             })
         });
+
+        impl $crate::resolvers::SnapshotValue for $struct_name {
+            fn snapshot(&self, base: usize) -> $crate::resolvers::snapshot::SnapshotEntry {
+                $crate::resolvers::snapshot::SnapshotEntry::members([
+                    $( (
+                        stringify!($member_name),
+                        $crate::resolvers::snapshot::SnapshotMember::snapshot_member(&self.$member_name, base),
+                    ), )*
+                ])
+            }
+        }
+    };
+}
+
+/// Implementation detail of [`impl_try_collector`]. Future resolving a single member
+#[doc(hidden)]
+#[macro_export]
+macro_rules! _impl_try_collector_member {
+    (required, $ctx:ident, $resolver:path) => {
+        $ctx.resolve(<$resolver>::resolver())
+    };
+    (optional, $ctx:ident, $resolver:path) => {
+        async { $crate::resolvers::Result::Ok($ctx.resolve(<$resolver>::resolver()).await.ok()) }
     };
 }
 
@@ -408,6 +681,17 @@ macro_rules! _impl_collector {
                 $( $member_name, )*
             })
         });
+
+        impl $crate::resolvers::SnapshotValue for $struct_name {
+            fn snapshot(&self, base: usize) -> $crate::resolvers::snapshot::SnapshotEntry {
+                $crate::resolvers::snapshot::SnapshotEntry::members([
+                    $( (
+                        stringify!($member_name),
+                        $crate::resolvers::snapshot::SnapshotMember::snapshot_member(&self.$member_name, base),
+                    ), )*
+                ])
+            }
+        }
     };
 }
 
@@ -417,14 +701,29 @@ pub use _impl_resolver_singleton as impl_resolver_singleton;
 pub use _impl_try_collector as impl_try_collector;
 pub use _matcharm_generator as matcharm_generator;
 pub mod cfg_image {
+    pub use _cfg_image_elf as ElfImage;
+    pub use _cfg_image_macho as MachOImage;
+    pub use _cfg_image_pe as PEImage;
+}
+/// Image types dispatched to by `collect` resolvers. Mach-O is excluded so existing resolvers
+/// need not provide an impl for it; they return an error on Mach-O images instead.
+pub mod cfg_collect {
     pub use _cfg_image_elf as ElfImage;
     pub use _cfg_image_pe as PEImage;
+    pub use _cfg_never as MachOImage;
 }
 
 pub trait Singleton {
     fn get(&self) -> Option<usize>;
 }
 
+/// Implemented by every resolution to record it in a [`snapshot::Snapshot`]. Singletons are
+/// implemented by their macro, other results must store every address relative to `base` and
+/// in a deterministic order so snapshots are stable across runs and rebasing
+pub trait SnapshotValue {
+    fn snapshot(&self, base: usize) -> snapshot::SnapshotEntry;
+}
+
 type AnyValue = Result<Arc<dyn Any + Send + Sync>>;
 
 #[derive(Debug)]
@@ -438,33 +737,754 @@ struct AsyncContextInnerWrite {
     resolvers: HashMap<TypeId, AnyValue>,
     pending_resolvers: HashMap<TypeId, Vec<oneshot::Sender<AnyValue>>>,
     queue: Vec<(Pattern, oneshot::Sender<PatternMatches>)>,
+    section_priority: Option<Vec<String>>,
+    deduplicated_scans: usize,
+    /// Matches of every scan keyed by the resolver which queued it, only recorded when
+    /// collecting candidates via [`resolve_candidates`]
+    candidates: Option<HashMap<&'static str, Vec<usize>>>,
+    /// Every scan along with the resolver which queued it, only recorded when checking
+    /// signature health via [`health::signature_health`] or recording a [`trace::ResolveTrace`]
+    scans: Option<Vec<ScanRecord>>,
 }
 
+/// Pattern scanned on behalf of a resolver, see [`health::signature_health`] and
+/// [`trace::ResolveTrace`]
+#[derive(Debug, Clone)]
+pub struct ScanRecord {
+    pub resolver: &'static str,
+    pub pattern: Pattern,
+    pub matches: usize,
+}
+
+/// Thread-local variable access decoded by [`AsyncContext::tls_access`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TlsAccess {
+    /// Address of the module's `_tls_index` variable
+    pub index_address: usize,
+    /// TLS slot of the module as currently stored at `index_address`. Only meaningful for live
+    /// images as the loader assigns it at load time
+    pub index: u32,
+    /// Offset of the variable within the module's TLS block
+    pub offset: u32,
+}
+
+/// Maximum number of instructions [`AsyncContext::tls_access`] decodes looking for the access
+const MAX_TLS_ACCESS_INSTRUCTIONS: usize = 12;
+
+/// Longest signature [`AsyncContext::minimal_signature`] will try before giving up
+pub const MAX_SIGNATURE_LEN: usize = 128;
+
+/// Default section scan order used by [`AsyncContext`]. Code is most likely to contain matches
+/// so it is scanned first
+pub const DEFAULT_SECTION_PRIORITY: &[&str] = &[".text", "__text"];
+
 struct AsyncContextInnerRead<'data> {
     write: Mutex<AsyncContextInnerWrite>,
     image: &'data Image<'data>,
+    pre_resolved: PreResolved,
+}
+
+/// Singleton addresses known ahead of time (e.g. from a previous run) that are used instead of
+/// scanning. Speeds up iterating on one resolver in a large collector by skipping the ones that
+/// already work
+#[derive(Debug, Default, Clone)]
+pub struct PreResolved {
+    addresses: HashMap<String, usize>,
+}
+impl PreResolved {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Use `address` for the singleton resolver named `name`
+    pub fn insert<S: Into<String>>(&mut self, name: S, address: usize) {
+        self.addresses.insert(name.into(), address);
+    }
+    /// Collect every resolved singleton of a previous snapshot, relocating to `image`
+    pub fn from_snapshot(image: &Image<'_>, snapshot: &snapshot::Snapshot) -> Self {
+        Self {
+            addresses: snapshot
+                .entries
+                .iter()
+                .filter_map(|(name, entry)| match entry {
+                    snapshot::SnapshotEntry::Rva(rva) => {
+                        Some((name.clone(), image.base_address.wrapping_add(*rva)))
+                    }
+                    _ => None,
+                })
+                .collect(),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
 }
 
 #[derive(Clone)]
 pub struct AsyncContext<'data> {
     read: Arc<AsyncContextInnerRead<'data>>,
+    /// Name of the resolver this context was handed to
+    resolver: Option<&'static str>,
 }
 
 impl<'data> AsyncContext<'data> {
-    fn new(image: &'data Image<'data>) -> Self {
+    fn new(image: &'data Image<'data>, pre_resolved: PreResolved) -> Self {
         Self {
             read: Arc::new(AsyncContextInnerRead {
                 write: Default::default(),
                 image,
+                pre_resolved,
             }),
+            resolver: None,
         }
     }
     pub fn image(&self) -> &Image<'_> {
         self.read.image
     }
+    /// Set the order in which sections are scanned by name. Sections not listed are scanned
+    /// afterwards in image order. Affects the order of matches returned by scans queued after
+    /// this call. Defaults to [`DEFAULT_SECTION_PRIORITY`]
+    pub fn set_section_priority<S: Into<String>>(&self, names: impl IntoIterator<Item = S>) {
+        self.read.write.lock().unwrap().section_priority =
+            Some(names.into_iter().map(Into::into).collect());
+    }
+    /// Current section scan order by name
+    pub fn section_priority(&self) -> Vec<String> {
+        self.read
+            .write
+            .lock()
+            .unwrap()
+            .section_priority
+            .clone()
+            .unwrap_or_else(|| {
+                DEFAULT_SECTION_PRIORITY
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect()
+            })
+    }
+    /// Pre-resolved address of the singleton resolver `name` if one was supplied and it still
+    /// holds for this image. The address must lie within a section, addresses in code must start
+    /// a known function and `verify`, declared with `impl_resolver_singleton!(all, Name, verify =
+    /// "..", ..)`, must match at it. Rejected addresses are discarded with a warning so the
+    /// resolver scans as usual
+    pub fn pre_resolved(&self, name: &str, verify: Option<&str>) -> Option<usize> {
+        let address = *self.read.pre_resolved.addresses.get(name)?;
+        let reject = |reason: &str| -> Option<usize> {
+            tracing::warn!("ignoring pre-resolved {name} = {address:#x}: {reason}");
+            None
+        };
+        let image = self.image();
+        let Ok(section) = image.memory.get_section_containing(address) else {
+            return reject("outside of image");
+        };
+        if section.kind() == object::SectionKind::Text
+            && !matches!(image.get_root_function(address), Ok(Some(f)) if f.range.start == address)
+        {
+            return reject("does not start a known function");
+        }
+        if let Some(verify) = verify {
+            let verified = Pattern::new(verify)
+                .map_err(|e| ResolveError::Msg(format!("invalid pattern: {e}").into()))
+                .and_then(|pattern| self.at(address).verify(&pattern));
+            if let Err(err) = verified {
+                return reject(&err.to_string());
+            }
+        }
+        Some(address)
+    }
+    /// Number of queued scans that were skipped so far because an identical pattern was queued
+    /// in the same stage
+    pub fn deduplicated_scans(&self) -> usize {
+        self.read.write.lock().unwrap().deduplicated_scans
+    }
+    /// Image sections in scan order
+    fn ordered_sections(&self) -> Vec<&crate::NamedMemorySection<'_>> {
+        let priority = self.section_priority();
+        let mut sections = self.image().memory.sections().iter().collect::<Vec<_>>();
+        // stable sort preserves image order for unlisted sections
+        sections.sort_by_key(|s| {
+            priority
+                .iter()
+                .position(|p| p == s.name())
+                .unwrap_or(priority.len())
+        });
+        sections
+    }
+    /// Read little endian `T` at `address`, returning an error if any byte lies outside of the
+    /// image's mapped sections
+    pub fn read_le<T: FromLeBytes>(&self, address: usize) -> Result<T> {
+        Ok(self.image().memory.read_le(address)?)
+    }
+    /// Read the rel32 displacement at `address` and return the address it points to (relative
+    /// to the end of the displacement)
+    pub fn read_rel32(&self, address: usize) -> Result<usize> {
+        let offset = self.read_le::<i32>(address)?;
+        address
+            .checked_add(4)
+            .and_then(|next| next.checked_add_signed(offset as isize))
+            .context("rel32 target out of address space")
+    }
+    /// Read a table of `count` consecutive `jmp rel32` thunks starting at `address` spaced
+    /// `stride` bytes apart, returning the address each thunk jumps to
+    pub fn read_thunk_table(
+        &self,
+        address: usize,
+        count: usize,
+        stride: usize,
+    ) -> Result<Vec<usize>> {
+        (0..count)
+            .map(|i| {
+                let thunk = address + i * stride;
+                if self.read_le::<u8>(thunk)? != 0xe9 {
+                    bail_out!(format!("expected jmp rel32 thunk at {thunk:#x}"));
+                }
+                self.read_rel32(thunk + 1)
+            })
+            .collect()
+    }
+    /// Scan for tables of `count` consecutive `jmp rel32` thunks spaced `stride` bytes apart
+    /// (e.g. import stubs or vtable thunks), returning the table address and thunk targets for
+    /// each match
+    pub async fn scan_thunk_tables(
+        &self,
+        count: usize,
+        stride: usize,
+    ) -> Result<Vec<(usize, Vec<usize>)>> {
+        if count == 0 || stride < 5 {
+            bail_out!("thunk table requires count > 0 and stride >= 5");
+        }
+        let padding = " ??".repeat(stride - 5) + " ";
+        let pattern = vec!["e9 ?? ?? ?? ??"; count].join(&padding);
+        self.scan(Pattern::new(pattern).unwrap())
+            .await
+            .into_iter()
+            .map(|table| Ok((table, self.read_thunk_table(table, count, stride)?)))
+            .collect()
+    }
     pub async fn scan(&self, pattern: Pattern) -> Vec<usize> {
         self.scan_tagged((), pattern).await.2
     }
+    /// Like [`Self::scan`] but each match is annotated with the section it lies in, e.g. to
+    /// spot code signatures accidentally matching data. Matches moved outside of every section
+    /// by a custom offset are dropped
+    pub async fn scan_all_located(&self, pattern: Pattern) -> Vec<(usize, crate::SectionId)> {
+        let memory = &self.image().memory;
+        self.scan(pattern)
+            .await
+            .into_iter()
+            .filter_map(|address| Some((address, memory.section_id(address)?)))
+            .collect()
+    }
+    /// Like [`Self::scan`] but each match is annotated with the start of its containing root
+    /// function (from `.pdata` or equivalent), or `None` for matches outside any known function
+    /// such as data signatures
+    pub async fn scan_all_with_function(
+        &self,
+        pattern: Pattern,
+    ) -> Result<Vec<(usize, Option<usize>)>> {
+        self.scan(pattern)
+            .await
+            .into_iter()
+            .map(|hit| {
+                let function = self.image().get_root_function(hit)?;
+                Ok((hit, function.map(|f| f.range.start)))
+            })
+            .collect()
+    }
+    /// Scan for `pattern` and map each match to the start of its containing root function,
+    /// returning each function once in order of first match. Matches outside any known function
+    /// (e.g. when the image has no exception table) are returned as is
+    pub async fn scan_unique_by_function(&self, pattern: Pattern) -> Result<Vec<usize>> {
+        let mut seen = HashSet::new();
+        let mut functions = vec![];
+        for hit in self.scan(pattern).await {
+            let function = self
+                .image()
+                .get_root_function(hit)?
+                .map(|f| f.range.start)
+                .unwrap_or(hit);
+            if seen.insert(function) {
+                functions.push(function);
+            }
+        }
+        Ok(functions)
+    }
+    /// Scan for `pattern`, returning each match address along with the bytes it matched
+    /// (starting at the beginning of the pattern, i.e. before any custom offset `|`)
+    pub async fn scan_slices(&self, pattern: Pattern) -> Result<Vec<(usize, &'data [u8])>> {
+        use crate::MemoryTrait;
+
+        let image: &'data Image<'data> = self.read.image;
+        let (_, pattern, matches) = self.scan_tagged((), pattern).await;
+        matches
+            .into_iter()
+            .map(|address| {
+                let start = address - pattern.custom_offset;
+                Ok((
+                    address,
+                    image.memory.range(start..start + pattern.simple.len())?,
+                ))
+            })
+            .collect()
+    }
+    /// Bind `address` to this context for chaining navigation steps
+    pub fn at(&self, address: usize) -> Located<'_, 'data> {
+        Located { ctx: self, address }
+    }
+    /// Scan for `pattern` expecting exactly one match
+    pub async fn scan_one(&self, pattern: Pattern) -> Result<Located<'_, 'data>> {
+        Ok(self.at(ensure_one(self.scan(pattern).await)?))
+    }
+    /// Return the first match of `pattern` in section scan order (see
+    /// [`Self::set_section_priority`]) and address order within a section
+    pub async fn scan_first(&self, pattern: Pattern) -> Option<usize> {
+        self.scan(pattern).await.into_iter().next()
+    }
+    /// Return the match of `pattern` closest to `hint`, e.g. the address found on a previous
+    /// build when layout shifts slightly across minor updates. Ties prefer the lower address
+    pub async fn scan_nearest(&self, pattern: Pattern, hint: usize) -> Option<usize> {
+        self.scan(pattern)
+            .await
+            .into_iter()
+            .min_by_key(|&address| (address.abs_diff(hint), address))
+    }
+    /// Try alternative signatures in order of preference, returning the address matched by the
+    /// first pattern that matches exactly once along with its index. All patterns are scanned
+    /// together, a pattern matching more than once is skipped rather than accepted
+    pub async fn scan_first_unique(&self, patterns: &[Pattern]) -> Result<(usize, usize)> {
+        let results = join_all(patterns.iter().map(|p| self.scan(p.clone()))).await;
+        for (i, matches) in results.into_iter().enumerate() {
+            if let [address] = matches.as_slice() {
+                return Ok((*address, i));
+            }
+        }
+        bail_out!(format!(
+            "none of {} patterns matched exactly once",
+            patterns.len()
+        ));
+    }
+    /// Validate `address` against the image's function table, snapping it to the start of the
+    /// containing root function. If `strict` is set an address that is not already a function
+    /// start is an error instead
+    pub fn snap_to_function(&self, address: usize, strict: bool) -> Result<usize> {
+        let start = self
+            .image()
+            .get_root_function(address)?
+            .context("address is not contained in any known function")?
+            .range
+            .start;
+        if strict && start != address {
+            bail_out!(format!(
+                "address {address:#x} is not a function start (function begins at {start:#x})"
+            ));
+        }
+        Ok(start)
+    }
+    /// Return the set of functions called directly or transitively from `func_start`, following
+    /// calls up to `depth` levels deep. Only direct calls into executable sections are followed
+    /// and disassembly of each function stops at the bounds of its root function
+    pub fn call_targets(&self, func_start: usize, depth: usize) -> Result<Vec<usize>> {
+        use crate::disassemble::{disassemble, Control};
+        use iced_x86::{FlowControl, OpKind};
+
+        let image = self.image();
+        let is_code = |address: usize| {
+            image
+                .memory
+                .get_section_containing(address)
+                .map(|s| s.kind() == object::SectionKind::Text)
+                .unwrap_or(false)
+        };
+
+        let mut visited = HashSet::from([func_start]);
+        let mut targets = vec![];
+        let mut level = vec![func_start];
+        for _ in 0..depth {
+            let mut next = vec![];
+            for function in level {
+                let root = image.get_root_function(function)?.map(|f| f.range);
+                disassemble(image, function, |inst| {
+                    let ip = inst.ip() as usize;
+                    if root.as_ref().map(|r| !r.contains(&ip)).unwrap_or(false) {
+                        return Ok(Control::Break);
+                    }
+                    if inst.flow_control() == FlowControl::Call
+                        && inst.op0_kind() == OpKind::NearBranch64
+                    {
+                        let target = inst.near_branch_target() as usize;
+                        if is_code(target) && visited.insert(target) {
+                            next.push(target);
+                        }
+                    }
+                    Ok(Control::Continue)
+                })?;
+            }
+            targets.extend(&next);
+            level = next;
+        }
+        targets.sort();
+        Ok(targets)
+    }
+    /// Decode the first compiler generated `switch` jump table of the function containing
+    /// `dispatch`, returning case targets indexed by case value. Recognizes tables of image base
+    /// relative entries (MSVC: `lea base, [__ImageBase]; mov r32, [base + idx*4 + table]`) and
+    /// table relative entries (clang/GCC: `lea base, [table]; movsxd r, [base + idx*4]`). The
+    /// number of cases is taken from the preceding `cmp idx, imm` bounds check
+    pub fn jump_table(&self, dispatch: usize) -> Result<Vec<usize>> {
+        use iced_x86::{Decoder, DecoderOptions, Mnemonic, OpKind, Register};
+
+        let image = self.image();
+        let range = image
+            .get_root_function_range(dispatch)?
+            .context("address is not contained in any known function")?;
+
+        let mut leas = HashMap::new();
+        let mut bound = None;
+        let instructions = image
+            .readable_slices(range)
+            .into_iter()
+            .flat_map(|(address, data)| {
+                Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE)
+            });
+        for inst in instructions {
+            match inst.mnemonic() {
+                Mnemonic::Cmp
+                    if inst.op0_kind() == OpKind::Register
+                        && matches!(
+                            inst.op1_kind(),
+                            OpKind::Immediate8 | OpKind::Immediate32 | OpKind::Immediate8to32
+                        ) =>
+                {
+                    bound = Some(inst.immediate(1) as usize + 1);
+                }
+                Mnemonic::Lea if inst.is_ip_rel_memory_operand() => {
+                    leas.insert(
+                        inst.op0_register().full_register(),
+                        inst.ip_rel_memory_address() as usize,
+                    );
+                }
+                Mnemonic::Mov | Mnemonic::Movsxd
+                    if inst.op_count() == 2
+                        && inst.op1_kind() == OpKind::Memory
+                        && inst.memory_index() != Register::None
+                        && inst.memory_index_scale() == 4 =>
+                {
+                    let Some(&base) = leas.get(&inst.memory_base().full_register()) else {
+                        continue;
+                    };
+                    let count = bound.context("jump table bounds check not found")?;
+                    let table = base.wrapping_add(inst.memory_displacement64() as usize);
+                    let len = count * 4;
+                    let slices = image.readable_slices(table..table.saturating_add(len));
+                    let Some((_, entries)) = slices
+                        .into_iter()
+                        .next()
+                        .filter(|(address, data)| *address == table && data.len() == len)
+                    else {
+                        bail_out!(format!("jump table at {table:#x} is not readable"));
+                    };
+                    return entries
+                        .chunks_exact(4)
+                        .map(|entry| {
+                            let entry = i32::from_le_bytes(entry.try_into().unwrap());
+                            base.checked_add_signed(entry as isize)
+                                .context("jump table entry out of address space")
+                        })
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        bail_out!("no jump table found");
+    }
+    /// Shortest signature of whole instructions starting at `address` which matches nowhere
+    /// else in the image. Relative branch targets and RIP-relative displacements are wildcarded
+    /// so the signature survives relinking. Fails if no unique signature is found within
+    /// [`MAX_SIGNATURE_LEN`] bytes
+    pub fn minimal_signature(&self, address: usize) -> Result<Pattern> {
+        use iced_x86::{Decoder, DecoderOptions, OpKind};
+
+        let image = self.image();
+        let slices = image.readable_slices(address..address.saturating_add(MAX_SIGNATURE_LEN + 15));
+        let Some((_, data)) = slices
+            .into_iter()
+            .next()
+            .filter(|(start, _)| *start == address)
+        else {
+            bail_out!(format!("{address:#x} is not readable"));
+        };
+        let mut decoder = Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE);
+
+        let mut sig: Vec<Option<u8>> = vec![];
+        let mut candidates: Option<Vec<usize>> = None;
+        while sig.len() < MAX_SIGNATURE_LEN && decoder.can_decode() {
+            let inst = decoder.decode();
+            if inst.is_invalid() {
+                bail_out!(format!("invalid instruction at {:#x}", inst.ip()));
+            }
+            let offsets = decoder.get_constant_offsets(&inst);
+            let start = inst.ip() as usize - address;
+            let mut bytes = data[start..start + inst.len()]
+                .iter()
+                .map(|b| Some(*b))
+                .collect::<Vec<_>>();
+            if inst.is_ip_rel_memory_operand() && offsets.has_displacement() {
+                let offset = offsets.displacement_offset();
+                bytes[offset..offset + offsets.displacement_size()].fill(None);
+            }
+            if inst.op_kinds().any(|op| {
+                matches!(
+                    op,
+                    OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64
+                )
+            }) && offsets.has_immediate()
+            {
+                let offset = offsets.immediate_offset();
+                bytes[offset..offset + offsets.immediate_size()].fill(None);
+            }
+            sig.extend(bytes);
+
+            // trailing wildcards do not contribute to matching
+            let Some(last) = sig.iter().rposition(Option::is_some) else {
+                continue;
+            };
+            let pattern = Pattern::new(
+                sig[..=last]
+                    .iter()
+                    .map(|b| b.map_or("??".to_string(), |b| format!("{b:02x}")))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+            .unwrap();
+
+            // only the matches of the previous prefix can match the longer signature
+            let matches = match candidates {
+                None => image.scan_readable(&pattern, 0..usize::MAX, 1),
+                Some(candidates) => candidates
+                    .into_iter()
+                    .filter(|&a| {
+                        let len = pattern.simple.len();
+                        image
+                            .readable_slices(a..a.saturating_add(len))
+                            .first()
+                            .is_some_and(|&(start, data)| {
+                                start == a && data.len() == len && pattern.is_match(data, a, 0)
+                            })
+                    })
+                    .collect(),
+            };
+            if matches == [address] {
+                return Ok(pattern);
+            }
+            candidates = Some(matches);
+        }
+        bail_out!(format!(
+            "no unique signature within {MAX_SIGNATURE_LEN} bytes at {address:#x}"
+        ));
+    }
+    /// Decode the MSVC thread-local access sequence starting at `address` (within a few
+    /// instructions), i.e.
+    ///
+    /// ```text
+    /// mov eax, [rip+_tls_index]
+    /// mov rcx, gs:[58h]            ; ThreadLocalStoragePointer
+    /// mov rcx, [rcx+rax*8]         ; TLS block of the module
+    /// mov rax, [rcx+offset]
+    /// ```
+    ///
+    /// The index load and TEB access may appear in either order. Globals stored per thread
+    /// cannot be followed with a plain RIP-relative read so this recovers the slot and offset
+    /// instead
+    pub fn tls_access(&self, address: usize) -> Result<TlsAccess> {
+        use crate::MemoryTrait;
+        use iced_x86::{Code, Decoder, DecoderOptions, Register};
+
+        let data = self.image().memory.range_from(address..)?;
+        let mut decoder = Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE);
+
+        let mut index = None;
+        let mut array = None;
+        let mut block = None;
+        for _ in 0..MAX_TLS_ACCESS_INSTRUCTIONS {
+            if !decoder.can_decode() {
+                break;
+            }
+            let inst = decoder.decode();
+            if inst.is_invalid() {
+                break;
+            }
+            let dst = inst.op0_register().full_register();
+
+            if inst.code() == Code::Mov_r32_rm32 && inst.is_ip_rel_memory_operand() {
+                index = Some((dst, inst.ip_rel_memory_address() as usize));
+            } else if inst.code() == Code::Mov_r64_rm64
+                && inst.memory_segment() == Register::GS
+                && inst.memory_base() == Register::None
+                && inst.memory_displacement64() == 0x58
+            {
+                array = Some(dst);
+            } else if let (Some((index_reg, _)), Some(array_reg)) = (index, array) {
+                if inst.memory_base() == array_reg
+                    && inst.memory_index() == index_reg
+                    && inst.memory_index_scale() == 8
+                {
+                    block = Some(dst);
+                } else if let Some(block_reg) = block {
+                    if inst.memory_base() == block_reg && inst.memory_index() == Register::None {
+                        let (_, index_address) = index.unwrap();
+                        return Ok(TlsAccess {
+                            index_address,
+                            index: self.read_le::<u32>(index_address)?,
+                            offset: inst.memory_displacement32(),
+                        });
+                    }
+                }
+            }
+        }
+        bail_out!(format!("no TLS access found at {address:#x}"));
+    }
+    /// Scan for `pattern`, returning an error once `max` matches are found. Resolvers expecting
+    /// a unique result can use `max = 2` to surface overly loose signatures. Unlike
+    /// [`Self::scan`] the pattern is scanned on its own rather than batched, so that scanning
+    /// stops as soon as the cap is reached instead of walking the rest of the image
+    pub async fn scan_capped(&self, pattern: Pattern, max: usize) -> Result<Vec<usize>> {
+        let mut matches = vec![];
+        for section in self.ordered_sections() {
+            let data = section.data();
+            for span in self.image().readable_spans(section) {
+                let budget = patternsleuth_scanner::ScanBudget {
+                    max_matches: Some(max.saturating_sub(matches.len())),
+                    ..Default::default()
+                };
+                let Ok(res) = patternsleuth_scanner::scan_pattern_with_budget(
+                    &[&pattern],
+                    section.address() + span.start,
+                    &data[span],
+                    budget,
+                )
+                .swap_remove(0) else {
+                    bail_out!(format!("ambiguous pattern, exceeded cap of {max} matches"));
+                };
+                matches.extend(res);
+            }
+        }
+        matches.sort_unstable();
+        Ok(matches)
+    }
+    /// Find the last match of `pattern` before `address`, searching backward only as far as the
+    /// start of the section containing `address`
+    pub fn scan_last_before(&self, pattern: &Pattern, address: usize) -> Result<Option<usize>> {
+        let section = self.image().memory.get_section_containing(address)?;
+        // allow matches which start before `address` but extend past it
+        let end = (address + pattern.simple.len() - 1).min(section.address() + section.len());
+        let matches = self
+            .image()
+            .scan_readable(pattern, section.address()..end, 1);
+        Ok(matches.into_iter().filter(|m| *m < address).max())
+    }
+    /// Test `pattern` at `base + i * stride` for each `i` in `0..count`, returning the indices
+    /// which match. Useful for confirming the layout of a suspected vtable or struct array
+    /// without scanning whole sections. Addresses outside the image never match
+    pub fn scan_strided(
+        &self,
+        pattern: &Pattern,
+        base: usize,
+        stride: usize,
+        count: usize,
+    ) -> Vec<usize> {
+        use crate::Matchable;
+
+        let memory = &self.image().memory;
+        (0..count)
+            .filter(|&i| {
+                i.checked_mul(stride)
+                    .and_then(|offset| base.checked_add(offset))
+                    .is_some_and(|address| matches!(memory.captures(pattern, address), Ok(Some(_))))
+            })
+            .collect()
+    }
+    /// Scan non-code sections for `pattern` testing only addresses aligned to `stride`. Much
+    /// faster than [`Self::scan`] for pointer and vtable tables but only finds aligned matches
+    pub fn scan_data_aligned(&self, pattern: &Pattern, stride: usize) -> Vec<usize> {
+        self.ordered_sections()
+            .into_iter()
+            .filter(|s| s.kind() != object::SectionKind::Text)
+            .flat_map(|s| {
+                self.image()
+                    .scan_readable(pattern, s.address()..s.address() + s.len(), stride)
+            })
+            .collect()
+    }
+    /// Scan for `pattern` only within the body of the root function containing `func_start` so
+    /// matches cannot spill into neighboring functions. Matches are returned in address order
+    pub fn scan_in_function(&self, func_start: usize, pattern: &Pattern) -> Result<Vec<usize>> {
+        let range = self
+            .image()
+            .get_root_function_range(func_start)?
+            .context("address is not contained in any known function")?;
+        Ok(self.image().scan_readable(pattern, range, 1))
+    }
+    /// Scan for `pattern` within the virtual address range `start..end`, e.g. as copied from a
+    /// debugger. The range may span multiple sections but must be fully covered by them. Only
+    /// matches lying entirely within the range are returned
+    pub fn scan_va_range(&self, pattern: &Pattern, start: usize, end: usize) -> Result<Vec<usize>> {
+        if start >= end {
+            bail_out!(format!("empty address range {start:#x}..{end:#x}"));
+        }
+        let covered = self
+            .image()
+            .memory
+            .sections()
+            .iter()
+            .map(|section| {
+                let (from, to) = (
+                    start.max(section.address()),
+                    end.min(section.address() + section.len()),
+                );
+                to.saturating_sub(from)
+            })
+            .sum::<usize>();
+        if covered != end - start {
+            bail_out!(format!(
+                "address range {start:#x}..{end:#x} is not fully contained in loaded sections"
+            ));
+        }
+        Ok(self.image().scan_readable(pattern, start..end, 1))
+    }
+    /// Scan for `pattern` within `window` bytes relative to the exported symbol `export`. The
+    /// window is clamped to the section containing the export. Anchoring to an export is more
+    /// robust across builds than scanning the entire image when a nearby function is exported
+    pub fn scan_near_export(
+        &self,
+        export: &str,
+        window: Range<isize>,
+        pattern: &Pattern,
+    ) -> Result<Vec<usize>> {
+        let address = self
+            .image()
+            .export(export)
+            .context("export not found in image")?;
+        let section = self.image().memory.get_section_containing(address)?;
+        let section_range = section.address()..section.address() + section.len();
+        let clamp = |offset: isize| {
+            address
+                .saturating_add_signed(offset)
+                .clamp(section_range.start, section_range.end)
+                - section_range.start
+        };
+        let (start, end) = (clamp(window.start), clamp(window.end));
+        if start >= end {
+            return Ok(vec![]);
+        }
+        Ok(self.image().scan_readable(
+            pattern,
+            section_range.start + start..section_range.start + end,
+            1,
+        ))
+    }
     pub async fn scan_tagged2<T: Copy>(&self, tag: T, pattern: Pattern) -> Vec<(T, usize)> {
         self.scan_tagged(tag, pattern)
             .await
@@ -480,6 +1500,22 @@ impl<'data> AsyncContext<'data> {
             lock.queue.push((pattern, tx));
         }
         let PatternMatches { pattern, matches } = rx.await.unwrap();
+        if let Some(resolver) = self.resolver {
+            let mut lock = self.read.write.lock().unwrap();
+            if let Some(candidates) = &mut lock.candidates {
+                candidates
+                    .entry(resolver)
+                    .or_default()
+                    .extend(matches.iter().copied());
+            }
+            if let Some(scans) = &mut lock.scans {
+                scans.push(ScanRecord {
+                    resolver,
+                    pattern: pattern.clone(),
+                    matches: matches.len(),
+                });
+            }
+        }
         (tag, pattern, matches)
     }
     pub async fn resolve<T: Send + Sync + 'static>(
@@ -515,8 +1551,12 @@ impl<'data> AsyncContext<'data> {
             return rx.await.unwrap().map(|ok| ok.downcast::<T>().unwrap());
         }
 
-        // compute the resolver value
-        let resolver = (resolver.factory)(self);
+        // compute the resolver value with a context that knows which resolver it belongs to
+        let ctx = AsyncContext {
+            read: self.read.clone(),
+            resolver: Some(resolver_name::<T>()),
+        };
+        let resolver = (resolver.factory)(&ctx);
         let res = resolver.await.map(Arc::new);
 
         let cache: Result<Arc<dyn Any + Send + Sync>> = match res.as_ref() {
@@ -537,15 +1577,171 @@ impl<'data> AsyncContext<'data> {
     }
 }
 
-#[tracing::instrument(level = "debug", skip_all, fields(stages))]
+/// Address bound to an [`AsyncContext`] so navigation steps can be chained with `?`, e.g.
+/// `ctx.scan_one(pattern).await?.follow_call()?.snap_to_function()?.get()`
+#[derive(Clone, Copy)]
+pub struct Located<'ctx, 'data> {
+    ctx: &'ctx AsyncContext<'data>,
+    address: usize,
+}
+impl std::fmt::Debug for Located<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Located({:#x})", self.address)
+    }
+}
+impl<'ctx, 'data> Located<'ctx, 'data> {
+    pub fn get(self) -> usize {
+        self.address
+    }
+    fn to(self, address: usize) -> Self {
+        Self { address, ..self }
+    }
+    /// Move by a signed `offset`
+    pub fn offset(self, offset: isize) -> Result<Self> {
+        let address = self
+            .address
+            .checked_add_signed(offset)
+            .context("offset out of address space")?;
+        Ok(self.to(address))
+    }
+    /// Follow the `call rel32` or `jmp rel32` instruction at the current address
+    pub fn follow_call(self) -> Result<Self> {
+        match self.ctx.read_le::<u8>(self.address)? {
+            0xe8 | 0xe9 => Ok(self.to(self.ctx.read_rel32(self.address + 1)?)),
+            _ => {
+                bail_out!(format!("expected call or jmp at {:#x}", self.address));
+            }
+        }
+    }
+    /// Follow the rel32 displacement at the current address (see [`AsyncContext::read_rel32`])
+    pub fn follow_rip(self) -> Result<Self> {
+        Ok(self.to(self.ctx.read_rel32(self.address)?))
+    }
+    /// Read the pointer stored at the current address
+    pub fn deref(self) -> Result<Self> {
+        Ok(self.to(self.ctx.read_le::<usize>(self.address)?))
+    }
+    /// Snap to the start of the containing root function (see
+    /// [`AsyncContext::snap_to_function`])
+    pub fn snap_to_function(self) -> Result<Self> {
+        Ok(self.to(self.ctx.snap_to_function(self.address, false)?))
+    }
+    /// Decode the thread-local access at the current address (see
+    /// [`AsyncContext::tls_access`])
+    pub fn tls_access(self) -> Result<TlsAccess> {
+        self.ctx.tls_access(self.address)
+    }
+    /// Ensure `pattern` matches starting at the current address
+    pub fn verify(self, pattern: &Pattern) -> Result<Self> {
+        use crate::Matchable;
+
+        if self
+            .ctx
+            .image()
+            .memory
+            .captures(pattern, self.address)?
+            .is_none()
+        {
+            bail_out!(format!("pattern did not match at {:#x}", self.address));
+        }
+        Ok(self)
+    }
+}
+
+/// Configured by [`set_max_scan_concurrency`], 0 selects the default
+static MAX_SCAN_CONCURRENCY: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+/// Set the maximum number of threads used for scanning. Every entry point ([`eval`] and
+/// everything built on it, [`resolve_threaded`] and [`resolve_matrix`]) runs on a shared pool of
+/// this size so batch scanning doesn't starve the machine. Defaults to the number of logical
+/// cores, a `limit` of 0 restores the default. Takes effect for calls started afterwards
+pub fn set_max_scan_concurrency(limit: usize) {
+    MAX_SCAN_CONCURRENCY.store(limit, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Current limit set by [`set_max_scan_concurrency`]
+pub fn max_scan_concurrency() -> usize {
+    match MAX_SCAN_CONCURRENCY.load(std::sync::atomic::Ordering::Relaxed) {
+        0 => std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        limit => limit,
+    }
+}
+
+thread_local! {
+    /// Whether the current thread belongs to a pool built by [`scan_pool`]
+    static ON_SCAN_POOL: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Shared pool of `threads` threads, built on first use and kept for the lifetime of the process
+fn scan_pool(threads: usize) -> Result<Arc<rayon::ThreadPool>> {
+    static POOLS: Mutex<Vec<Arc<rayon::ThreadPool>>> = Mutex::new(Vec::new());
+    let mut pools = POOLS.lock().unwrap();
+    if let Some(pool) = pools.iter().find(|p| p.current_num_threads() == threads) {
+        return Ok(pool.clone());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .start_handler(|_| ON_SCAN_POOL.with(|on| on.set(true)))
+        .build()
+        .map_err(|e| ResolveError::Msg(format!("failed to build thread pool: {e}").into()))?;
+    pools.push(Arc::new(pool));
+    Ok(pools.last().unwrap().clone())
+}
+
+/// Run `f` on a scan pool of `threads` threads capped by [`max_scan_concurrency`], 0 selecting
+/// the limit itself. Calls made from a scan pool thread, e.g. per image of [`resolve_matrix`],
+/// run in place so nesting never exceeds the limit
+pub(crate) fn on_scan_pool<R: Send>(threads: usize, f: impl FnOnce() -> R + Send) -> R {
+    if ON_SCAN_POOL.with(|on| on.get()) {
+        return f();
+    }
+    let limit = max_scan_concurrency();
+    let threads = match threads {
+        0 => limit,
+        threads => threads.min(limit),
+    };
+    match scan_pool(threads) {
+        Ok(pool) => pool.install(f),
+        Err(err) => {
+            tracing::warn!("{err}, scanning on the current thread pool");
+            f()
+        }
+    }
+}
+
+/// Drive `f` to completion on the current thread, batching the scans of all pending resolvers
+/// into a single pass over the image whenever they stall. This is the executor behind
+/// [`resolve`] and [`Image::resolve`] so no async runtime is required by callers. Use it
+/// directly to run ad hoc async code against an [`AsyncContext`]:
+///
+/// ```ignore
+/// let (a, b) = eval(&image, |ctx| {
+///     Box::pin(async { futures::join!(ctx.resolve(A::resolver()), ctx.resolve(B::resolver())) })
+/// });
+/// ```
 pub fn eval<F, T: Send + Sync>(image: &Image<'_>, f: F) -> T
 where
     F: for<'ctx> FnOnce(&'ctx AsyncContext<'_>) -> BoxFuture<'ctx, T> + Send + Sync,
 {
-    {
+    eval_with(image, PreResolved::default(), f)
+}
+
+/// Like [`eval`] but singleton resolvers found in `pre_resolved` return their cached address
+/// instead of scanning
+#[tracing::instrument(level = "debug", skip_all, fields(stages, deduplicated))]
+pub fn eval_with<F, T: Send + Sync>(image: &Image<'_>, pre_resolved: PreResolved, f: F) -> T
+where
+    F: for<'ctx> FnOnce(&'ctx AsyncContext<'_>) -> BoxFuture<'ctx, T> + Send + Sync,
+{
+    let eval_span = tracing::Span::current();
+    on_scan_pool(0, move || {
+        let _eval_span = eval_span.enter();
         tracing::debug!("starting eval");
 
-        let ctx = AsyncContext::new(image);
+        let ctx = AsyncContext::new(image, pre_resolved);
         let (rx, tx) = std::sync::mpsc::channel();
 
         let scope = new_relay_scope!();
@@ -573,20 +1769,37 @@ where
 
             if let Ok(res) = tx.try_recv() {
                 tracing::Span::current().record("stages", i);
+                tracing::Span::current().record("deduplicated", ctx.deduplicated_scans());
                 break res;
             } else {
                 let queue: Vec<_> = std::mem::take(&mut ctx.read.write.lock().unwrap().queue);
                 let (patterns, rx): (Vec<_>, Vec<_>) = queue.into_iter().unzip();
-                let setup = patterns.iter().collect::<Vec<_>>();
 
-                let span = tracing::debug_span!("patterns", patterns = setup.len()).entered();
+                // resolvers sharing an anchor (e.g. the same string) queue identical patterns so
+                // only scan each distinct pattern once and fan the results out
+                let mut setup = vec![];
+                let mut distinct = HashMap::new();
+                let slots = patterns
+                    .iter()
+                    .map(|p| {
+                        *distinct.entry(p).or_insert_with(|| {
+                            setup.push(p);
+                            setup.len() - 1
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let deduplicated = patterns.len() - setup.len();
+                ctx.read.write.lock().unwrap().deduplicated_scans += deduplicated;
+
+                let span = tracing::debug_span!("patterns", patterns = setup.len(), deduplicated)
+                    .entered();
                 for p in &setup {
                     tracing::debug!("pattern = {p:?}");
                 }
 
-                let mut all_results = rx.into_iter().map(|rx| (rx, vec![])).collect::<Vec<_>>();
+                let mut all_results = vec![vec![]; setup.len()];
 
-                for section in image.memory.sections() {
+                for section in ctx.ordered_sections() {
                     let span = tracing::debug_span!(
                         "section",
                         section = section.name(),
@@ -595,17 +1808,21 @@ where
                     )
                     .entered();
 
-                    let base_address = section.address();
                     let data = section.data();
 
-                    let scan_results =
-                        patternsleuth_scanner::scan_pattern(&setup, base_address, data);
-
                     let mut total = 0;
 
-                    for (i, res) in scan_results.iter().enumerate() {
-                        total += res.len();
-                        all_results[i].1.extend(res)
+                    // pages of a live image may be guarded or decommitted so only scan spans
+                    // which are safe to read, see `Image::unreadable_ranges`
+                    for span in ctx.image().readable_spans(section) {
+                        let base_address = section.address() + span.start;
+                        let scan_results =
+                            patternsleuth_scanner::scan_pattern(&setup, base_address, &data[span]);
+
+                        for (i, res) in scan_results.iter().enumerate() {
+                            total += res.len();
+                            all_results[i].extend(res)
+                        }
                     }
 
                     span.record("results", total);
@@ -613,12 +1830,13 @@ where
 
                 drop(span);
 
-                for ((rx, matches), pattern) in all_results.into_iter().zip(patterns) {
+                for ((rx, slot), pattern) in rx.into_iter().zip(slots).zip(patterns) {
+                    let matches = all_results[slot].clone();
                     rx.send(PatternMatches { pattern, matches }).unwrap();
                 }
             }
         }
-    }
+    })
 }
 
 pub fn resolve<T: Send + Sync>(
@@ -629,6 +1847,57 @@ pub fn resolve<T: Send + Sync>(
         .map(|ok| Arc::<T>::into_inner(ok).unwrap())
 }
 
+/// Unqualified type name of resolver `T`, matching [`NamedResolver::name`]
+fn resolver_name<T>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Like [`resolve`] but also returns the matches of every scan queued while resolving, keyed by
+/// the resolver which queued it. Shows the candidates each resolver considered before narrowing
+/// them down, e.g. to debug one which resolves to the wrong address on a new build
+pub fn resolve_candidates<T: Send + Sync>(
+    image: &Image<'_>,
+    resolver: &'static ResolverFactory<T>,
+) -> (Result<T>, HashMap<&'static str, Vec<usize>>) {
+    let (res, mut candidates) = eval(image, |ctx| {
+        Box::pin(async {
+            ctx.read.write.lock().unwrap().candidates = Some(Default::default());
+            let res = ctx.resolve(resolver).await;
+            let candidates = ctx.read.write.lock().unwrap().candidates.take();
+            (res, candidates.unwrap_or_default())
+        })
+    });
+    for matches in candidates.values_mut() {
+        matches.sort();
+        matches.dedup();
+    }
+    (res.map(|ok| Arc::<T>::into_inner(ok).unwrap()), candidates)
+}
+
+/// Like [`resolve`] but scans run on a shared pool of `num_threads` threads, capped by
+/// [`max_scan_concurrency`], giving predictable CPU usage when scanning many images in a batch.
+/// Results are identical to [`resolve`] as scan results are always collected in address order
+pub fn resolve_threaded<T: Send + Sync>(
+    image: &Image<'_>,
+    resolver: &'static ResolverFactory<T>,
+    num_threads: usize,
+) -> Result<T> {
+    on_scan_pool(num_threads, || resolve(image, resolver))
+}
+
+/// Like [`resolve`] but using `pre_resolved` addresses where available (see [`PreResolved`])
+pub fn resolve_with<T: Send + Sync>(
+    image: &Image<'_>,
+    resolver: &'static ResolverFactory<T>,
+    pre_resolved: PreResolved,
+) -> Result<T> {
+    eval_with(image, pre_resolved, |ctx| {
+        Box::pin(async { ctx.resolve(resolver).await })
+    })
+    .map(|ok| Arc::<T>::into_inner(ok).unwrap())
+}
+
 pub fn resolve_many(
     image: &Image<'_>,
     resolvers: &[fn() -> &'static DynResolverFactory],
@@ -638,3 +1907,49 @@ pub fn resolve_many(
         Box::pin(async { join_all(fns.into_iter().map(|f| f(ctx))).await })
     })
 }
+
+/// Results of resolving the same resolvers against many images, see [`resolve_matrix`]
+pub struct ResolveMatrix {
+    /// Names of the resolvers in column order
+    pub resolvers: Vec<&'static str>,
+    /// One row per image, in input order, with the result of each resolver
+    pub rows: Vec<(BuildId, Vec<Result<Arc<dyn Resolution>>>)>,
+}
+impl ResolveMatrix {
+    /// Build ids of the images each resolver succeeded on, keyed by resolver name
+    pub fn summary(&self) -> BTreeMap<&'static str, Vec<&BuildId>> {
+        self.resolvers
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let resolved = self
+                    .rows
+                    .iter()
+                    .filter(|(_, results)| results[i].is_ok())
+                    .map(|(id, _)| id)
+                    .collect();
+                (*name, resolved)
+            })
+            .collect()
+    }
+}
+
+/// Resolve `resolvers` against each of `images` in parallel. Intended for maintaining
+/// signatures across a library of games, e.g. to see which fields a change breaks on which build.
+/// Images and their scans share the pool of [`max_scan_concurrency`] threads
+pub fn resolve_matrix(images: &[Image<'_>], resolvers: &[&NamedResolver]) -> ResolveMatrix {
+    use rayon::prelude::*;
+
+    let getters = resolvers.iter().map(|r| r.getter).collect::<Vec<_>>();
+    let rows = || {
+        images
+            .par_iter()
+            .map(|image| (image.build_id(), resolve_many(image, &getters)))
+            .collect::<Vec<_>>()
+    };
+    let rows = on_scan_pool(0, rows);
+    ResolveMatrix {
+        resolvers: resolvers.iter().map(|r| r.name).collect(),
+        rows,
+    }
+}
@@ -0,0 +1,232 @@
+//! Deterministic snapshots of resolver results for signature regression testing.
+//!
+//! A snapshot records one line per resolver, sorted by name. Each result is recorded through
+//! [`SnapshotValue`] which stores addresses relative to the image base, with maps sorted by
+//! name, so snapshots remain stable across runs and rebasing. Committing a snapshot of a
+//! reference binary turns "did this change break a resolver?" into a plain text diff.
+
+use std::{collections::BTreeMap, fmt::Display, str::FromStr, sync::Arc};
+
+use crate::Image;
+
+use super::{NamedResolver, Resolution, Result, SnapshotValue};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotEntry {
+    /// Resolved singleton address relative to image base
+    Rva(usize),
+    /// Name to address map result with addresses relative to image base
+    Map(BTreeMap<String, usize>),
+    /// Any other result, with addresses relative to image base
+    Value(String),
+    /// Resolution failed
+    Error(String),
+}
+
+impl SnapshotEntry {
+    fn new(image: &Image<'_>, result: &Result<Arc<dyn Resolution>>) -> Self {
+        match result {
+            Ok(res) => res.snapshot(image.base_address),
+            Err(err) => Self::Error(escape(&err.to_string())),
+        }
+    }
+    /// Map of names to absolute addresses, recorded relative to `base`
+    pub fn map<'a, N: Into<String>>(
+        base: usize,
+        addresses: impl IntoIterator<Item = (N, &'a usize)>,
+    ) -> Self {
+        Self::Map(
+            addresses
+                .into_iter()
+                .map(|(name, address)| (name.into(), address.wrapping_sub(base)))
+                .collect(),
+        )
+    }
+    /// Free form value which must not contain absolute addresses
+    pub fn value(value: impl AsRef<str>) -> Self {
+        Self::Value(escape(value.as_ref()))
+    }
+    /// Value of a collector recording each member in declaration order, `None` for optional
+    /// members which did not resolve
+    pub fn members<'a>(
+        members: impl IntoIterator<Item = (&'a str, Option<SnapshotEntry>)>,
+    ) -> Self {
+        let members = members
+            .into_iter()
+            .map(|(name, entry)| match entry {
+                Some(entry) => format!("{name}=({entry})"),
+                None => format!("{name}=none"),
+            })
+            .collect::<Vec<_>>();
+        // members are escaped already
+        Self::Value(members.join(" "))
+    }
+}
+
+impl Display for SnapshotEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rva(rva) => write!(f, "rva 0x{rva:x}"),
+            Self::Map(map) => {
+                write!(f, "map")?;
+                for (name, rva) in map {
+                    write!(f, " {name}=0x{rva:x}")?;
+                }
+                Ok(())
+            }
+            Self::Value(value) => write!(f, "value {value}"),
+            Self::Error(err) => write!(f, "error {err}"),
+        }
+    }
+}
+
+/// Member of a collector as stored in its struct, see [`SnapshotEntry::members`]
+pub trait SnapshotMember {
+    fn snapshot_member(&self, base: usize) -> Option<SnapshotEntry>;
+}
+impl<T: SnapshotValue> SnapshotMember for Arc<T> {
+    fn snapshot_member(&self, base: usize) -> Option<SnapshotEntry> {
+        Some(self.snapshot(base))
+    }
+}
+impl<T: SnapshotValue> SnapshotMember for Option<Arc<T>> {
+    fn snapshot_member(&self, base: usize) -> Option<SnapshotEntry> {
+        self.as_ref().map(|m| m.snapshot(base))
+    }
+}
+impl<T: SnapshotValue> SnapshotMember for Result<Arc<T>> {
+    fn snapshot_member(&self, base: usize) -> Option<SnapshotEntry> {
+        Some(match self {
+            Ok(m) => m.snapshot(base),
+            Err(err) => SnapshotEntry::Error(escape(&err.to_string())),
+        })
+    }
+}
+
+fn parse_rva(value: &str) -> std::result::Result<usize, std::num::ParseIntError> {
+    usize::from_str_radix(value.strip_prefix("0x").unwrap_or(value), 16)
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub entries: BTreeMap<String, SnapshotEntry>,
+}
+
+impl Snapshot {
+    /// Resolve `resolvers` against `image` and record the results
+    pub fn capture(image: &Image<'_>, resolvers: &[&NamedResolver]) -> Self {
+        let getters = resolvers.iter().map(|r| r.getter).collect::<Vec<_>>();
+        let results = image.resolve_many(&getters);
+        Self::from_results(image, resolvers.iter().map(|r| r.name).zip(results.iter()))
+    }
+
+    /// Build snapshot from previously obtained results
+    pub fn from_results<'a, N: Into<String>>(
+        image: &Image<'_>,
+        results: impl IntoIterator<Item = (N, &'a Result<Arc<dyn Resolution>>)>,
+    ) -> Self {
+        Self {
+            entries: results
+                .into_iter()
+                .map(|(name, result)| (name.into(), SnapshotEntry::new(image, result)))
+                .collect(),
+        }
+    }
+
+    /// Compare against an `expected` snapshot, returning every resolver whose result differs
+    pub fn compare(&self, expected: &Snapshot) -> Vec<SnapshotDiff> {
+        let mut names = expected
+            .entries
+            .keys()
+            .chain(self.entries.keys())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let expected = expected.entries.get(name);
+                let actual = self.entries.get(name);
+                (expected != actual).then(|| SnapshotDiff {
+                    name: name.clone(),
+                    expected: expected.cloned(),
+                    actual: actual.cloned(),
+                })
+            })
+            .collect()
+    }
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (name, entry) in &self.entries {
+            writeln!(f, "{name} {entry}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Snapshot {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut entries = BTreeMap::new();
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let mut split = line.splitn(3, ' ');
+            let (Some(name), Some(kind)) = (split.next(), split.next()) else {
+                anyhow::bail!("malformed snapshot line {}: {line:?}", i + 1);
+            };
+            // an empty map has no value
+            let value = split.next().unwrap_or_default();
+            let entry = match kind {
+                "rva" => SnapshotEntry::Rva(parse_rva(value)?),
+                "map" => SnapshotEntry::Map(
+                    value
+                        .split(' ')
+                        .filter(|e| !e.is_empty())
+                        .map(|e| -> anyhow::Result<_> {
+                            let (name, rva) = e.split_once('=').ok_or_else(|| {
+                                anyhow::anyhow!("malformed map entry {e:?} on line {}", i + 1)
+                            })?;
+                            Ok((name.to_string(), parse_rva(rva)?))
+                        })
+                        .collect::<anyhow::Result<_>>()?,
+                ),
+                "value" => SnapshotEntry::Value(value.to_string()),
+                "error" => SnapshotEntry::Error(value.to_string()),
+                _ => anyhow::bail!("unknown snapshot entry kind {kind:?} on line {}", i + 1),
+            };
+            entries.insert(name.to_string(), entry);
+        }
+        Ok(Self { entries })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    pub name: String,
+    pub expected: Option<SnapshotEntry>,
+    pub actual: Option<SnapshotEntry>,
+}
+
+impl Display for SnapshotDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fmt = |e: &Option<SnapshotEntry>| {
+            e.as_ref()
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "<missing>".into())
+        };
+        write!(
+            f,
+            "{}: expected {}, found {}",
+            self.name,
+            fmt(&self.expected),
+            fmt(&self.actual)
+        )
+    }
+}
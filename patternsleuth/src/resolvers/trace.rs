@@ -0,0 +1,220 @@
+//! Persistent traces of resolver scans for offline debugging.
+//!
+//! A trace records every pattern scanned while resolving, how many candidates it matched and
+//! the result each resolver finally chose. When a resolver misbehaves on a binary that can't be
+//! shared, the user can [`ResolveTrace::record`] and [`ResolveTrace::save`] a trace which is then
+//! [`ResolveTrace::load`]ed and inspected without access to the binary itself.
+
+use std::{fmt::Display, path::Path, str::FromStr};
+
+use futures::future::join_all;
+use patternsleuth_scanner::Pattern;
+
+use crate::Image;
+
+use super::{eval, snapshot::Snapshot, NamedResolver};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceScan {
+    /// Resolver which queued the scan
+    pub resolver: String,
+    /// Number of candidates the pattern matched
+    pub matches: usize,
+    pub pattern: Pattern,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResolveTrace {
+    /// Build id of the traced image, see [`Image::build_id`]
+    pub build_id: Option<String>,
+    /// Every scan in the order its results were received
+    pub scans: Vec<TraceScan>,
+    /// Result chosen by each resolver
+    pub results: Snapshot,
+}
+
+impl ResolveTrace {
+    /// Resolve `resolvers` against `image`, recording every scan along the way
+    pub fn record(image: &Image<'_>, resolvers: &[&NamedResolver]) -> Self {
+        let fns = resolvers
+            .iter()
+            .map(|r| (r.getter)().factory)
+            .collect::<Vec<_>>();
+        let (results, scans) = eval(image, |ctx| {
+            Box::pin(async {
+                ctx.read.write.lock().unwrap().scans = Some(vec![]);
+                let results = join_all(fns.into_iter().map(|f| f(ctx))).await;
+                let scans = ctx.read.write.lock().unwrap().scans.take();
+                (results, scans.unwrap_or_default())
+            })
+        });
+        Self {
+            build_id: Some(image.build_id().to_string()),
+            scans: scans
+                .into_iter()
+                .map(|s| TraceScan {
+                    resolver: s.resolver.to_string(),
+                    matches: s.matches,
+                    pattern: s.pattern,
+                })
+                .collect(),
+            results: Snapshot::from_results(
+                image,
+                resolvers.iter().map(|r| r.name).zip(results.iter()),
+            ),
+        }
+    }
+
+    /// Scans queued by resolver `name`
+    pub fn scans_for<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a TraceScan> + 'a {
+        self.scans.iter().filter(move |s| s.resolver == name)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        Ok(std::fs::write(path, self.to_string())?)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+}
+
+impl Display for ResolveTrace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(build_id) = &self.build_id {
+            writeln!(f, "build {build_id}")?;
+        }
+        for scan in &self.scans {
+            writeln!(
+                f,
+                "scan {} {} {}",
+                scan.resolver, scan.matches, scan.pattern
+            )?;
+        }
+        for line in self.results.to_string().lines() {
+            writeln!(f, "result {line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ResolveTrace {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut trace = Self::default();
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let Some((kind, rest)) = line.split_once(' ') else {
+                anyhow::bail!("malformed trace line {}: {line:?}", i + 1);
+            };
+            match kind {
+                "build" => trace.build_id = Some(rest.to_string()),
+                "scan" => {
+                    let mut split = rest.splitn(3, ' ');
+                    let (Some(resolver), Some(matches), Some(pattern)) =
+                        (split.next(), split.next(), split.next())
+                    else {
+                        anyhow::bail!("malformed scan on trace line {}: {line:?}", i + 1);
+                    };
+                    trace.scans.push(TraceScan {
+                        resolver: resolver.to_string(),
+                        matches: matches.parse()?,
+                        pattern: Pattern::new(pattern)?,
+                    });
+                }
+                "result" => trace
+                    .results
+                    .entries
+                    .append(&mut rest.parse::<Snapshot>()?.entries),
+                _ => anyhow::bail!("unknown trace entry kind {kind:?} on line {}", i + 1),
+            }
+        }
+        Ok(trace)
+    }
+}
+
+#[cfg(all(test, feature = "image-pe"))]
+mod test {
+    use super::*;
+    use crate::test::{build_pe, Section};
+
+    #[test]
+    fn test_record_save_load() {
+        let data = build_pe(
+            0x2000,
+            &[Section {
+                name: ".text",
+                rva: 0x1000,
+                virtual_size: 0x200,
+                raw_offset: 0x400,
+                raw_size: 0x200,
+            }],
+        );
+        let image = Image::read::<&str>(None, &data, None, false).unwrap();
+        let resolvers = super::super::resolvers().take(4).collect::<Vec<_>>();
+        let trace = ResolveTrace::record(&image, &resolvers);
+        assert_eq!(Some(image.build_id().to_string()), trace.build_id);
+        assert_eq!(resolvers.len(), trace.results.entries.len());
+
+        let path =
+            std::env::temp_dir().join(format!("patternsleuth-trace-{}.txt", std::process::id()));
+        trace.save(&path).unwrap();
+        let loaded = ResolveTrace::load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(trace, loaded.unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let trace = ResolveTrace {
+            build_id: Some("gnu 0011aabb".into()),
+            scans: vec![
+                TraceScan {
+                    resolver: "GMalloc".into(),
+                    matches: 2,
+                    pattern: Pattern::new("48 8B 0D | ?? ?? ?? ?? [ E8 ]").unwrap(),
+                },
+                TraceScan {
+                    resolver: "GMalloc".into(),
+                    matches: 0,
+                    pattern: Pattern::new("CC").unwrap(),
+                },
+            ],
+            results: "GMalloc rva 0x1010\nGUObjectArray error not found\n"
+                .parse()
+                .unwrap(),
+        };
+        let text = trace.to_string();
+        assert_eq!(
+            "build gnu 0011aabb\n\
+             scan GMalloc 2 48 8B 0D | ?? ?? ?? ?? [ E8 ]\n\
+             scan GMalloc 0 CC\n\
+             result GMalloc rva 0x1010\n\
+             result GUObjectArray error not found\n",
+            text
+        );
+        assert_eq!(trace, text.parse::<ResolveTrace>().unwrap());
+        assert_eq!(2, trace.scans_for("GMalloc").count());
+    }
+
+    #[test]
+    fn test_malformed() {
+        for (text, error) in [
+            ("build", "malformed trace line 1"),
+            ("scan GMalloc 2", "malformed scan on trace line 1"),
+            ("\nscan GMalloc two cc", "invalid digit"),
+            ("scan GMalloc 2 zz", "in pattern \"zz\""),
+            (
+                "result GMalloc rva",
+                "cannot parse integer from empty string",
+            ),
+            (
+                "match GMalloc 0x10",
+                "unknown trace entry kind \"match\" on line 1",
+            ),
+        ] {
+            let err = text.parse::<ResolveTrace>().unwrap_err();
+            assert!(err.to_string().contains(error), "{text:?}: {err}");
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use futures::try_join;
+
+use crate::resolvers::{
+    ensure_one, impl_resolver_singleton,
+    unreal::{
+        kismet::{FFrameStep, FFrameStepExplicitProperty},
+        util,
+    },
+};
+
+/// public: class UActorComponent * __cdecl AActor::GetComponentByClass(class TSubclassOf<class UActorComponent>) const
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AActorGetComponentByClass(pub usize);
+impl_resolver_singleton!(all, AActorGetComponentByClass, |ctx| async {
+    // execGetComponentByClass steps the class argument off the VM stack and forwards it to
+    // GetComponentByClass so it is the only other function called by the thunk
+    let (step, step_explicit_property, execs) = try_join!(
+        ctx.resolve(FFrameStep::resolver()),
+        ctx.resolve(FFrameStepExplicitProperty::resolver()),
+        util::find_native_execs(ctx, "GetComponentByClass"),
+    )?;
+    let ignore = [step.0, step_explicit_property.0];
+
+    let mut callees = HashSet::new();
+    for exec in execs {
+        callees.extend(
+            util::find_calls(ctx.image(), exec)?
+                .into_iter()
+                .map(|c| c.callee)
+                .filter(|c| !ignore.contains(c)),
+        );
+    }
+    Ok(Self(ensure_one(callees)?))
+});
+
+/// public: bool __cdecl AActor::Destroy(bool, bool)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct AActorDestroy(pub usize);
+impl_resolver_singleton!(all, AActorDestroy, |ctx| async {
+    // logged when the actor has no world to forward UWorld::DestroyActor to
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "Destroying %s, which doesn't have a valid world pointer\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
+/// public: class AActor * __cdecl UWorld::SpawnActor(class UClass *, struct FTransform const *, struct FActorSpawnParameters const &)
+///
+/// Only the address is resolved, callers must match the engine version's signature. UE 4.x takes
+/// `(UClass*, FVector const*, FRotator const*, FActorSpawnParameters const&)` as an overload
+/// which forwards to the `FTransform` variant located here. The layout of
+/// `FActorSpawnParameters` also changes between versions
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UWorldSpawnActor(pub usize);
+impl_resolver_singleton!(all, UWorldSpawnActor, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "SpawnActor failed because no class was specified\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
@@ -1,11 +1,12 @@
 use std::fmt::{Debug, Display};
 
 use futures::future::join_all;
+use itertools::Itertools;
 
 use patternsleuth_scanner::Pattern;
 
 use crate::{
-    resolvers::{impl_resolver, Result},
+    resolvers::{impl_resolver, snapshot::SnapshotEntry, Result, SnapshotValue},
     MemoryTrait,
 };
 
@@ -44,6 +45,14 @@ impl Debug for AESKey {
 }
 
 impl_resolver!(collect, AESKeys);
+
+impl SnapshotValue for AESKeys {
+    fn snapshot(&self, _base: usize) -> SnapshotEntry {
+        let mut keys = self.0.iter().collect::<Vec<_>>();
+        keys.sort();
+        SnapshotEntry::value(keys.iter().join(" "))
+    }
+}
 impl_resolver!(PEImage, AESKeys, |ctx| async {
     #[derive(Debug, Clone, Copy)]
     enum KeyType {
@@ -5,7 +5,10 @@ use futures::{future::join_all, join};
 use patternsleuth_scanner::Pattern;
 
 use crate::{
-    resolvers::{ensure_one, impl_resolver, impl_resolver_singleton, Context},
+    resolvers::{
+        ensure_one, impl_resolver, impl_resolver_singleton, snapshot::SnapshotEntry, Context,
+        SnapshotValue,
+    },
     Addressable, Matchable,
 };
 
@@ -172,6 +175,28 @@ impl_resolver!(all, BlueprintLibraryInit, |ctx| async {
     })
 });
 
+impl SnapshotValue for BlueprintLibraryInit {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::map(
+            base,
+            [
+                ("uclass_compiled_in_defer", &self.uclass_compiled_in_defer),
+                ("uobject_compiled_in_defer", &self.uobject_compiled_in_defer),
+                ("construct_uclass", &self.construct_uclass),
+                (
+                    "get_private_static_class_body",
+                    &self.get_private_static_class_body,
+                ),
+                ("uobject_static_class", &self.uobject_static_class),
+                (
+                    "ublueprint_function_library_static_class",
+                    &self.ublueprint_function_library_static_class,
+                ),
+            ],
+        )
+    }
+}
+
 /// UFunction::Bind
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
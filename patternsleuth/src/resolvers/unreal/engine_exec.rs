@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+
+use futures::future::join_all;
+
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
+
+/// public: virtual bool __cdecl UEngine::Exec(class UWorld *, wchar_t const *, class FOutputDevice &)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UEngineExec(pub usize);
+impl_resolver_singleton!(all, UEngineExec, |ctx| async {
+    // UEngine::Exec parses commands inline via FParse::Command before dispatching to handlers:
+    //   FParse::Command(&Cmd, TEXT("GAMEVER")) || FParse::Command(&Cmd, TEXT("GAMEVERSION"))
+    // find the function referencing both strings
+    let commands = ["GAMEVER\0", "GAMEVERSION\0"];
+
+    let fns = join_all(commands.iter().map(|command| async move {
+        let strings = ctx.scan(util::utf16_pattern(command)).await;
+        let refs = util::scan_xrefs(ctx, &strings).await;
+        util::root_functions(ctx, &refs).map(HashSet::<usize>::from_iter)
+    }))
+    .await
+    .into_iter()
+    .collect::<crate::resolvers::Result<Vec<_>>>()?;
+
+    let candidates = fns.iter().skip(1).fold(fns[0].clone(), |acc, f| &acc & f);
+
+    Ok(Self(ensure_one(candidates)?))
+});
@@ -6,7 +6,7 @@ use itertools::Itertools;
 use patternsleuth_scanner::Pattern;
 
 use crate::{
-    resolvers::{bail_out, impl_resolver, try_ensure_one},
+    resolvers::{bail_out, impl_resolver, snapshot::SnapshotEntry, try_ensure_one, SnapshotValue},
     MemoryAccessorTrait,
 };
 
@@ -72,6 +72,12 @@ impl_resolver!(all, EngineVersion, |ctx| async {
     )
 });
 
+impl SnapshotValue for EngineVersion {
+    fn snapshot(&self, _base: usize) -> SnapshotEntry {
+        SnapshotEntry::value(self.to_string())
+    }
+}
+
 /// currently seems to be 4.22+
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
@@ -84,6 +90,12 @@ pub struct EngineVersionStrings {
     pub build_version: String,
 }
 impl_resolver!(collect, EngineVersionStrings);
+
+impl SnapshotValue for EngineVersionStrings {
+    fn snapshot(&self, _base: usize) -> SnapshotEntry {
+        SnapshotEntry::value(format!("{self:?}"))
+    }
+}
 // "++UE5+Release-{}.{}"
 impl_resolver!(ElfImage, EngineVersionStrings, |ctx| async {
     use crate::resolvers::{ensure_one, unreal::util};
@@ -0,0 +1,35 @@
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util, Context};
+
+/// public: class UFunction * __cdecl UObject::FindFunctionChecked(class FName) const
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UObjectFindFunctionChecked(pub usize);
+impl_resolver_singleton!(all, UObjectFindFunctionChecked, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern("Failed to find function %s in %s\0"))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
+/// public: class UFunction * __cdecl UClass::FindFunctionByName(class FName, enum EIncludeSuperFlag::Type) const
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UClassFindFunctionByName(pub usize);
+impl_resolver_singleton!(all, UClassFindFunctionByName, |ctx| async {
+    // FindFunctionChecked inlines FindFunction so the first call is to
+    // GetClass()->FindFunctionByName(InName)
+    let checked = ctx.resolve(UObjectFindFunctionChecked::resolver()).await?;
+    let first = util::find_calls(ctx.image(), checked.0)?
+        .into_iter()
+        .next()
+        .context("FindFunctionChecked contains no calls")?;
+    Ok(Self(first.callee))
+});
@@ -5,7 +5,7 @@ use futures::future::join_all;
 use patternsleuth_scanner::Pattern;
 
 use crate::{
-    resolvers::{impl_resolver_singleton, try_ensure_one},
+    resolvers::{ensure_one, impl_resolver_singleton, try_ensure_one, unreal::util},
     MemoryAccessorTrait,
 };
 
@@ -57,3 +57,25 @@ impl_resolver_singleton!(all, FTextFString, |ctx| async {
         },
     ))?))
 });
+
+/// public: static class FText __cdecl FText::FromString(class FString &&)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FTextFromString(pub usize);
+impl_resolver_singleton!(all, FTextFromString, |ctx| async {
+    // FromString constructs the text from the moved FString then sets the culture invariant
+    // (and on older versions initialized from string) flags on the result:
+    //   call FText::FText(FString&&)
+    //   or   dword ptr [reg + Flags], 2 | 0x12
+    let ctor = ctx.resolve(FTextFString::resolver()).await?;
+
+    let calls = ctx
+        .scan(Pattern::new(format!("e8 X0x{:X} 83 4? ?? ?2", ctor.0)).unwrap())
+        .await;
+    let fns = util::root_functions(ctx, &calls)?;
+
+    Ok(Self(ensure_one(fns)?))
+});
@@ -0,0 +1,53 @@
+use std::collections::HashSet;
+
+use futures::join;
+
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
+
+/// void __cdecl CollectGarbageInternal(enum EObjectFlags, bool)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CollectGarbageInternal(pub usize);
+impl_resolver_singleton!(all, CollectGarbageInternal, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern("Collecting garbage%s\0"))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
+/// void __cdecl CollectGarbage(enum EObjectFlags, bool)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct CollectGarbage(pub usize);
+impl_resolver_singleton!(all, CollectGarbage, |ctx| async {
+    // CollectGarbage acquires the GC lock and calls CollectGarbageInternal. TryCollectGarbage
+    // calls it as well so exclude it by its log message
+    let (internal, try_strings) = join!(
+        ctx.resolve(CollectGarbageInternal::resolver()),
+        ctx.scan(util::utf16_pattern(
+            "TryCollectGarbage: forcing GC after %d skipped attempts.\0"
+        )),
+    );
+    let internal = internal?;
+
+    let try_refs = util::scan_xrefs(ctx, &try_strings).await;
+    let try_fns = util::root_functions(ctx, &try_refs)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    let calls = util::scan_xcalls(ctx, &[internal.0]).await;
+    let fns = util::root_functions(ctx, &calls)?
+        .into_iter()
+        .filter(|f| !try_fns.contains(f) && *f != internal.0)
+        .collect::<HashSet<_>>();
+
+    Ok(Self(ensure_one(fns)?))
+});
@@ -0,0 +1,65 @@
+use std::fmt::Debug;
+
+use iced_x86::{Code, Register};
+use itertools::Itertools as _;
+
+use crate::{
+    disassemble::{disassemble, Control},
+    image::Image,
+    resolvers::{impl_resolver_singleton, try_ensure_one, unreal::util, Result},
+};
+
+/// class FConfigCacheIni * GConfig
+///
+/// Found via `GConfig->GetArray(TEXT("Core.System"), ...)` style call sites where the config
+/// cache is loaded into RCX before the section name is passed in RDX
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GConfig(pub usize);
+impl_resolver_singleton!(collect, GConfig);
+impl_resolver_singleton!(PEImage, GConfig, |ctx| async {
+    let strings = ctx.scan(util::utf16_pattern("Core.System\0")).await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+
+    fn for_each(img: &Image<'_>, addr: usize) -> Result<Option<usize>> {
+        let Some(root) = img.get_root_function(addr)? else {
+            return Ok(None);
+        };
+        let f = root.range().start;
+
+        let mut is_match = false;
+        let mut rcx = None;
+
+        disassemble(img, f, |inst| {
+            let cur = inst.ip() as usize;
+            if !(f..=addr).contains(&cur) {
+                return Ok(Control::Break);
+            }
+            if addr == cur && inst.op0_register() == Register::RDX && rcx.is_some() {
+                is_match = true;
+                return Ok(Control::Break);
+            }
+
+            if inst.op0_register() == Register::RCX {
+                rcx = (inst.code() == Code::Mov_r64_rm64 && inst.is_ip_rel_memory_operand())
+                    .then(|| inst.ip_rel_memory_address() as usize);
+            }
+
+            Ok(Control::Continue)
+        })?;
+
+        Ok(is_match.then_some(rcx).flatten())
+    }
+
+    Ok(Self(try_ensure_one(
+        refs.into_iter()
+            .map(|addr| for_each(ctx.image(), addr))
+            .flatten_ok(),
+    )?))
+});
+impl_resolver_singleton!(ElfImage, GConfig, |_ctx| async {
+    super::bail_out!("ElfImage unimplemented");
+});
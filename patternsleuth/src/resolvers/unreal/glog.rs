@@ -0,0 +1,68 @@
+use std::fmt::Debug;
+
+use iced_x86::{Code, Register};
+use itertools::Itertools as _;
+
+use crate::{
+    disassemble::{disassemble, Control},
+    image::Image,
+    resolvers::{impl_resolver_singleton, try_ensure_one, unreal::util, Result},
+};
+
+/// class FOutputDeviceRedirector * GLog
+///
+/// Found via `GLog->Logf(TEXT("Cmd: %s"), ...)` call sites where the redirector pointer is loaded
+/// into RCX shortly before the format string
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GLog(pub usize);
+impl_resolver_singleton!(collect, GLog);
+impl_resolver_singleton!(PEImage, GLog, |ctx| async {
+    let strings = ctx.scan(util::utf16_pattern("Cmd: %s\0")).await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+
+    fn for_each(img: &Image<'_>, addr: usize) -> Result<Option<usize>> {
+        let Some(root) = img.get_root_function(addr)? else {
+            return Ok(None);
+        };
+        let f = root.range().start;
+
+        let mut is_match = false;
+        let mut rcx = None;
+
+        disassemble(img, f, |inst| {
+            let cur = inst.ip() as usize;
+            if !(f..=addr).contains(&cur) {
+                return Ok(Control::Break);
+            }
+            if addr == cur
+                && matches!(inst.op0_register(), Register::RDX | Register::R8)
+                && rcx.is_some()
+            {
+                is_match = true;
+                return Ok(Control::Break);
+            }
+
+            if inst.op0_register() == Register::RCX {
+                rcx = (inst.code() == Code::Mov_r64_rm64 && inst.is_ip_rel_memory_operand())
+                    .then(|| inst.ip_rel_memory_address() as usize);
+            }
+
+            Ok(Control::Continue)
+        })?;
+
+        Ok(is_match.then_some(rcx).flatten())
+    }
+
+    Ok(Self(try_ensure_one(
+        refs.into_iter()
+            .map(|addr| for_each(ctx.image(), addr))
+            .flatten_ok(),
+    )?))
+});
+impl_resolver_singleton!(ElfImage, GLog, |_ctx| async {
+    super::bail_out!("ElfImage unimplemented");
+});
@@ -6,9 +6,10 @@ use patternsleuth_scanner::Pattern;
 
 use crate::{
     resolvers::{
-        bail_out, ensure_one, impl_resolver, impl_resolver_singleton, try_ensure_one, Result,
+        bail_out, ensure_one, impl_resolver, impl_resolver_singleton, snapshot::SnapshotEntry,
+        try_ensure_one, Result, SnapshotValue,
     },
-    Addressable, Matchable, MemoryTrait,
+    Addressable, Matchable, MemoryAccessorTrait, MemoryTrait,
 };
 
 /// public: void __cdecl UObject::SkipFunction(struct FFrame &, void *const, class UFunction *)
@@ -87,6 +88,51 @@ impl_resolver_singleton!(ElfImage, GNatives, |ctx| async {
     bail_out!("failed to not find LEA instruction");
 });
 
+/// GNatives along with the number of populated entries (each pointing to an opcode handler)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GNativesTable {
+    pub address: usize,
+    /// `None` if no slot points to code, e.g. in an on-disk image where the table is only
+    /// filled during static initialization
+    pub len: Option<usize>,
+}
+impl_resolver!(all, GNativesTable, |ctx| async {
+    // EX_Max
+    const MAX_NATIVES: usize = 0x100;
+
+    let address = ctx.resolve(GNatives::resolver()).await?.0;
+    let mem = &ctx.image().memory;
+
+    // every slot is initialized to at least execUndefined so count entries until the first one
+    // that does not point to code
+    let len = (0..MAX_NATIVES)
+        .take_while(|i| {
+            mem.ptr(address + i * 8)
+                .and_then(|ptr| mem.get_section_containing(ptr))
+                .is_ok_and(|section| section.kind() == object::SectionKind::Text)
+        })
+        .count();
+
+    Ok(GNativesTable {
+        address,
+        len: (len != 0).then_some(len),
+    })
+});
+
+impl SnapshotValue for GNativesTable {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::value(format!(
+            "address=0x{:x} len={:?}",
+            self.address.wrapping_sub(base),
+            self.len
+        ))
+    }
+}
+
 /// public: void __cdecl FFrame::Step(class UObject *, void *const)
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
@@ -168,3 +214,15 @@ impl_resolver!(all, FFrameStepViaExec, |ctx| async {
             }),
     )
 });
+
+impl SnapshotValue for FFrameStepViaExec {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::map(
+            base,
+            [
+                ("step", &self.step),
+                ("step_explicit_property", &self.step_explicit_property),
+            ],
+        )
+    }
+}
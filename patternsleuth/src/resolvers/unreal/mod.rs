@@ -1,18 +1,29 @@
+pub mod actor;
 pub mod aes;
 pub mod blueprint_library;
+pub mod engine_exec;
 pub mod engine_version;
+pub mod find_function;
 pub mod fname;
 pub mod ftext;
 pub mod fuobject_hash_tables;
 pub mod game_loop;
+pub mod garbage_collection;
+pub mod gconfig;
 pub mod gengine;
+pub mod glog;
 pub mod gmalloc;
 pub mod guobject_array;
 pub mod kismet;
+pub mod net_driver;
 pub mod pak;
+pub mod platform_process;
 pub mod save_game;
+pub mod slate;
 pub mod static_construct_object;
 pub mod static_find_object;
+pub mod static_load_object;
+pub mod umg;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -27,7 +38,8 @@ use patternsleuth_scanner::Pattern;
 use crate::{
     disassemble::{disassemble, Control},
     resolvers::{
-        bail_out, ensure_one, impl_resolver, impl_resolver_singleton, try_ensure_one, Result,
+        bail_out, ensure_one, impl_resolver, impl_resolver_singleton, snapshot::SnapshotEntry,
+        try_ensure_one, Result, SnapshotValue,
     },
     Addressable, Image, Matchable, MemoryAccessorTrait,
 };
@@ -124,6 +136,75 @@ mod util {
         refs.into_iter().flatten().collect()
     }
 
+    /// Find `exec` thunks registered for the native UFunction `name` by locating the
+    /// `FNameNativePtrPair { "name", &exec }` entries of generated native registration tables
+    pub(crate) async fn find_native_execs(
+        ctx: &AsyncContext<'_>,
+        name: &str,
+    ) -> Result<Vec<usize>> {
+        let strings = ctx.scan(utf8_pattern(&format!("{name}\0"))).await;
+        let pairs = join_all(
+            strings
+                .iter()
+                .map(|s| ctx.scan(Pattern::from_bytes(usize::to_le_bytes(*s).into()).unwrap())),
+        )
+        .await;
+        pairs
+            .into_iter()
+            .flatten()
+            .map(|pair| ctx.read_le::<usize>(pair + 8))
+            .collect()
+    }
+
+    /// Read the native function table registered by the generated `StaticRegisterNatives`
+    /// function of `class_name`, mapping each function name to its exec thunk
+    pub(crate) async fn native_functions(
+        ctx: &AsyncContext<'_>,
+        class_name: &str,
+    ) -> Result<HashMap<String, usize>> {
+        let mem = &ctx.image().memory;
+
+        let strings = ctx.scan(utf16_pattern(&format!("{class_name}\0"))).await;
+
+        let refs = join_all(strings.iter().map(|s| {
+            ctx.scan(
+                Pattern::new(format!(
+                    // fragile (only 4.25-4.27 most likely)
+                    "4c 8d 0d [ ?? ?? ?? ?? ] 88 4c 24 70 4c 8d 05 ?? ?? ?? ?? 49 89 43 e0 48 8d 15 X0x{:x}",
+                    s
+                ))
+                .unwrap(),
+            )
+        }))
+        .await;
+
+        let cap = Pattern::new("4c 8d 0d [ ?? ?? ?? ?? ]").unwrap();
+
+        let register_natives_addr = try_ensure_one(refs.iter().flatten().map(|a| -> Result<_> {
+            Ok(ctx.image().memory.captures(&cap, *a)?.unwrap()[0].rip())
+        }))?;
+
+        let register_natives = Pattern::new("48 83 ec 28 e8 ?? ?? ?? ?? 41 b8 [ ?? ?? ?? ?? ] 48 8d 15 [ ?? ?? ?? ?? ] 48 8b c8 48 83 c4 28 e9 ?? ?? ?? ??").unwrap();
+
+        let captures = ctx
+            .image()
+            .memory
+            .captures(&register_natives, register_natives_addr);
+
+        if let Some([num, data]) = captures?.as_deref() {
+            let mut res = HashMap::new();
+
+            let ptr = data.rip();
+            for i in 0..(num.u32() as usize) {
+                let a = ptr + i * 0x10;
+                res.insert(mem.read_string(mem.ptr(a)?)?, mem.ptr(a + 8)?);
+            }
+            Ok(res)
+        } else {
+            bail_out!("did not match");
+        }
+    }
+
     pub(crate) fn root_functions<'a, I>(ctx: &AsyncContext<'_>, addresses: I) -> Result<Vec<usize>>
     where
         I: IntoIterator<Item = &'a usize> + Copy,
@@ -247,57 +328,55 @@ mod util {
 pub struct KismetSystemLibrary(pub HashMap<String, usize>);
 
 impl_resolver!(all, KismetSystemLibrary, |ctx| async {
-    let mem = &ctx.image().memory;
-
-    let s = Pattern::from_bytes(
-        "KismetSystemLibrary\x00"
-            .encode_utf16()
-            .flat_map(u16::to_le_bytes)
-            .collect(),
-    )
-    .unwrap();
-    let strings = ctx.scan(s).await;
-
-    let refs = join_all(strings.iter().map(|s| {
-        ctx.scan(
-            Pattern::new(format!(
-        // fragile (only 4.25-4.27 most likely)
-        "4c 8d 0d [ ?? ?? ?? ?? ] 88 4c 24 70 4c 8d 05 ?? ?? ?? ?? 49 89 43 e0 48 8d 15 X0x{:x}",
-        s
+    Ok(KismetSystemLibrary(
+        util::native_functions(ctx, "KismetSystemLibrary").await?,
     ))
-            .unwrap(),
-        )
-    }))
-    .await;
+});
 
-    let cap = Pattern::new("4c 8d 0d [ ?? ?? ?? ?? ]").unwrap();
+impl SnapshotValue for KismetSystemLibrary {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::map(base, &self.0)
+    }
+}
 
-    let register_natives_addr =
-        try_ensure_one(refs.iter().flatten().map(|a| -> Result<_> {
-            Ok(ctx.image().memory.captures(&cap, *a)?.unwrap()[0].rip())
-        }))?;
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct KismetStringLibrary(pub HashMap<String, usize>);
 
-    let register_natives = Pattern::new("48 83 ec 28 e8 ?? ?? ?? ?? 41 b8 [ ?? ?? ?? ?? ] 48 8d 15 [ ?? ?? ?? ?? ] 48 8b c8 48 83 c4 28 e9 ?? ?? ?? ??").unwrap();
+impl_resolver!(all, KismetStringLibrary, |ctx| async {
+    Ok(KismetStringLibrary(
+        util::native_functions(ctx, "KismetStringLibrary").await?,
+    ))
+});
 
-    let captures = ctx
-        .image()
-        .memory
-        .captures(&register_natives, register_natives_addr);
+impl SnapshotValue for KismetStringLibrary {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::map(base, &self.0)
+    }
+}
 
-    if let Some([num, data]) = captures?.as_deref() {
-        let mut res = HashMap::new();
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct GameplayStatics(pub HashMap<String, usize>);
 
-        let ptr = data.rip();
-        for i in 0..(num.u32() as usize) {
-            let a = ptr + i * 0x10;
-            res.insert(mem.read_string(mem.ptr(a)?)?, mem.ptr(a + 8)?);
-        }
-        Ok(KismetSystemLibrary(res))
-    } else {
-        bail_out!("did not match");
-    }
+impl_resolver!(all, GameplayStatics, |ctx| async {
+    Ok(GameplayStatics(
+        util::native_functions(ctx, "GameplayStatics").await?,
+    ))
 });
 
+impl SnapshotValue for GameplayStatics {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        SnapshotEntry::map(base, &self.0)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
     feature = "serde-resolvers",
@@ -362,6 +441,27 @@ impl_resolver_singleton!(all, UObjectBaseUtilityGetPathName, |ctx| async {
     Ok(Self(ensure_one(res.into_iter().flatten())?))
 });
 
+/// public: virtual void __cdecl UObject::Serialize(struct FStructuredArchive::FRecord)
+///
+/// Located via the archive debug data name pushed before serializing the name, outer and class.
+/// The debug data scope is compiled out of some shipping builds in which case this fails
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UObjectSerialize(pub usize);
+impl_resolver_singleton!(all, UObjectSerialize, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "UObject::Serialize, Name, Outer, Class\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
 /// useful for extracting strings from common patterns for analysis
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
@@ -390,6 +490,12 @@ impl_resolver!(all, UtilStringExtractor, |ctx| async {
     ))
 });
 
+impl SnapshotValue for UtilStringExtractor {
+    fn snapshot(&self, _base: usize) -> SnapshotEntry {
+        SnapshotEntry::value(format!("{:?}", self.0.iter().sorted().collect::<Vec<_>>()))
+    }
+}
+
 /// useful for extracting strings from common patterns for analysis
 #[derive(Debug, PartialEq)]
 #[cfg_attr(
@@ -414,3 +520,10 @@ impl_resolver!(all, A, |ctx| async {
         .map(|a| Ok(mem.rip4(a)?))
         .collect::<Result<HashSet<_>>>()?))
 });
+
+impl SnapshotValue for A {
+    fn snapshot(&self, base: usize) -> SnapshotEntry {
+        let rvas = self.0.iter().map(|a| a.wrapping_sub(base)).sorted();
+        SnapshotEntry::value(rvas.map(|rva| format!("0x{rva:x}")).join(" "))
+    }
+}
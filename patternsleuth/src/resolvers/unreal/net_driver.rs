@@ -0,0 +1,42 @@
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
+
+/// Every RPC sent from an actor ends up here so it is the place to observe or block replicated
+/// function calls
+///
+/// public: virtual void __cdecl UNetDriver::ProcessRemoteFunction(class AActor *, class UFunction *, void *, struct FOutParmRec *, struct FFrame *, class UObject *)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UNetDriverProcessRemoteFunction(pub usize);
+impl_resolver_singleton!(all, UNetDriverProcessRemoteFunction, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "UNetDriver::ProcessRemoteFunction: No owning connection for actor %s. Function %s will not be processed.\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
+/// Called every frame while recording a replay to serialize the frame into the replay stream
+///
+/// public: void __cdecl UDemoNetDriver::TickDemoRecord(float)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UDemoNetDriverTickDemoRecord(pub usize);
+impl_resolver_singleton!(all, UDemoNetDriverTickDemoRecord, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "UDemoNetDriver::TickDemoRecord: ReplayStreamer ERROR: %s\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
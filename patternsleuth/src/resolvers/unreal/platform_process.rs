@@ -0,0 +1,37 @@
+use crate::resolvers::{bail_out, ensure_one, impl_resolver_singleton, unreal::util};
+
+/// Loads a module (or returns the handle of one already loaded) searching the engine's module
+/// directories, the callable way for mods to locate sibling modules at runtime
+/// ```
+/// void * __cdecl FWindowsPlatformProcess::GetDllHandle(wchar_t const *)
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FPlatformProcessGetDllHandle(pub usize);
+impl_resolver_singleton!(all, FPlatformProcessGetDllHandle, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "Failed to load '%s' (GetLastError=%d)\0",
+        ))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let load_library = ensure_one(util::root_functions(ctx, &refs)?)?;
+
+    // the string is logged by LoadLibraryWithSearchPaths whose only caller is GetDllHandle. If
+    // it has been inlined the function found may be GetDllHandle itself, but it can't be told
+    // apart from LoadLibraryWithSearchPaths whose signature differs so that is an error
+    let calls = util::scan_xcalls(ctx, &[load_library]).await;
+    let mut callers = util::root_functions(ctx, &calls)?;
+    callers.sort();
+    callers.dedup();
+    if let [get_dll_handle] = callers.as_slice() {
+        return Ok(Self(*get_dll_handle));
+    }
+    bail_out!(format!(
+        "expected LoadLibraryWithSearchPaths at {load_library:#x} to have a single caller, found {}",
+        callers.len()
+    ));
+});
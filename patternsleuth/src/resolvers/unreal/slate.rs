@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+
+use futures::try_join;
+
+use crate::resolvers::{
+    ensure_one, impl_resolver_singleton,
+    unreal::{
+        kismet::{FFrameStep, FFrameStepExplicitProperty},
+        util,
+    },
+    AsyncContext, Result,
+};
+
+/// Registering an `IInputProcessor` is the supported way to see key and mouse events before any
+/// widget does so it doubles as an input hook for overlays
+///
+/// public: bool __cdecl FSlateApplication::RegisterInputPreProcessor(class TSharedPtr<class IInputProcessor, 0>, int)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FSlateApplicationRegisterInputPreProcessor(pub usize);
+impl_resolver_singleton!(
+    all,
+    FSlateApplicationRegisterInputPreProcessor,
+    |ctx| async {
+        let strings = ctx
+            .scan(util::utf16_pattern(
+                "A null input processor was passed to RegisterInputPreProcessor\0",
+            ))
+            .await;
+        let refs = util::scan_xrefs(ctx, &strings).await;
+        let fns = util::root_functions(ctx, &refs)?;
+        Ok(Self(ensure_one(fns)?))
+    }
+);
+
+/// Entry point for every key press routed through Slate, from the platform message handler as
+/// well as synthesized input. Runs the registered input pre-processors before bubbling the event
+/// through the focused widget path, so hooking it sees keys even if an input processor would
+/// swallow them
+///
+/// public: bool __cdecl FSlateApplication::ProcessKeyDownEvent(struct FKeyEvent const &)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct FSlateApplicationProcessKeyDownEvent(pub usize);
+impl_resolver_singleton!(all, FSlateApplicationProcessKeyDownEvent, |ctx| async {
+    // UWidgetInteractionComponent::PressKey and ReleaseKey share everything but forwarding the
+    // key to ProcessKeyDownEvent or ProcessKeyUpEvent. PressKey also forwards the character to
+    // ProcessKeyCharEvent, which is all SendKeyChar does
+    let (step, step_explicit_property) = try_join!(
+        ctx.resolve(FFrameStep::resolver()),
+        ctx.resolve(FFrameStepExplicitProperty::resolver()),
+    )?;
+    let ignore = [step.0, step_explicit_property.0];
+    let (press, release, send_char) = try_join!(
+        native_calls(ctx, "PressKey", &ignore),
+        native_calls(ctx, "ReleaseKey", &ignore),
+        native_calls(ctx, "SendKeyChar", &ignore),
+    )?;
+    Ok(Self(ensure_one(press.into_iter().filter(|f| {
+        !release.contains(f) && !send_char.contains(f)
+    }))?))
+});
+
+/// Functions called by the native thunks of UFunction `name`, other than `ignore`. Thunks which
+/// only forward to the implementation are followed so its calls are returned instead
+async fn native_calls(
+    ctx: &AsyncContext<'_>,
+    name: &str,
+    ignore: &[usize],
+) -> Result<HashSet<usize>> {
+    let calls = |f| -> Result<HashSet<usize>> {
+        Ok(util::find_calls(ctx.image(), f)?
+            .into_iter()
+            .map(|c| c.callee)
+            .filter(|c| !ignore.contains(c))
+            .collect())
+    };
+    let mut callees = HashSet::new();
+    for exec in util::find_native_execs(ctx, name).await? {
+        let exec_calls = calls(exec)?;
+        match exec_calls.iter().collect::<Vec<_>>().as_slice() {
+            [implementation] => callees.extend(calls(**implementation)?),
+            _ => callees.extend(exec_calls),
+        }
+    }
+    Ok(callees)
+}
@@ -1,3 +1,5 @@
+use futures::future::join_all;
+
 use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
 
 /// ```
@@ -18,3 +20,29 @@ impl_resolver_singleton!(all, StaticFindObjectFast, |ctx| async {
     let fns = util::root_functions(ctx, &refs)?;
     Ok(Self(ensure_one(fns)?))
 });
+
+/// ```
+/// class UObject * __cdecl StaticFindObject(class UClass *, class UObject *, wchar_t const *, bool)
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct StaticFindObject(pub usize);
+impl_resolver_singleton!(all, StaticFindObject, |ctx| async {
+    let strings = join_all([
+        ctx.scan(util::utf16_pattern(
+            "Illegal call to StaticFindObject() while serializing object data!\0",
+        )),
+        ctx.scan(util::utf16_pattern(
+            "Illegal call to StaticFindObject() while collecting garbage!\0",
+        )),
+    ])
+    .await
+    .concat();
+
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
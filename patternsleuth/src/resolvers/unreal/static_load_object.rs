@@ -0,0 +1,43 @@
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
+
+/// `LoadObject<T>` is inlined into its callers so `StaticLoadObject` is the callable entry point
+/// ```
+/// class UObject * __cdecl StaticLoadObject(class UClass *, class UObject *, wchar_t const *, wchar_t const *, unsigned int, class UPackageMap *, bool, struct FLinkerInstancingContext const *)
+/// class UObject * __cdecl StaticLoadObject(class UClass *, class UObject *, wchar_t const *, wchar_t const *, unsigned int, class UPackageMap *, bool)
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct StaticLoadObject(pub usize);
+impl_resolver_singleton!(all, StaticLoadObject, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern(
+            "Failed to find object '{ClassName} {OuterName}.{ObjectName}'\0",
+        ))
+        .await;
+
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
+
+/// ```
+/// class UClass * __cdecl StaticLoadClass(class UClass *, class UObject *, wchar_t const *, wchar_t const *, unsigned int, class UPackageMap *)
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct StaticLoadClass(pub usize);
+impl_resolver_singleton!(all, StaticLoadClass, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern("{0} is not a child class of {1}\0"))
+        .await;
+
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
@@ -0,0 +1,20 @@
+use crate::resolvers::{ensure_one, impl_resolver_singleton, unreal::util};
+
+/// `CreateWidget<T>` is a header template so every overload funnels into this, which validates
+/// the widget class and resolves the owning player before constructing the widget
+///
+/// private: static class UUserWidget * __cdecl UUserWidget::CreateInstanceInternal(class UObject *, class TSubclassOf<class UUserWidget>, class FName, class UWorld *, class ULocalPlayer *)
+#[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde-resolvers",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub struct UUserWidgetCreateInstanceInternal(pub usize);
+impl_resolver_singleton!(all, UUserWidgetCreateInstanceInternal, |ctx| async {
+    let strings = ctx
+        .scan(util::utf16_pattern("Abstract, Deprecated or Replaced classes are not allowed to be used to construct a user widget. {0} is one of these.\0"))
+        .await;
+    let refs = util::scan_xrefs(ctx, &strings).await;
+    let fns = util::root_functions(ctx, &refs)?;
+    Ok(Self(ensure_one(fns)?))
+});
@@ -9,10 +9,39 @@ pub struct Symbol {
     pub name: String,
 }
 impl Symbol {
+    /// Demangled name using [`DefaultDemangler`], or the raw name if it is not mangled
     pub fn demangle(&self) -> String {
-        let name = self.name.to_string().to_string();
-        msvc_demangler::demangle(&name, msvc_demangler::DemangleFlags::llvm())
-            .unwrap_or_else(|_| name.to_string())
+        self.demangle_with(&DefaultDemangler)
+    }
+    /// Demangled name using `demangler`, or the raw name if it could not be demangled
+    pub fn demangle_with(&self, demangler: &dyn Demangler) -> String {
+        demangler
+            .demangle(&self.name)
+            .unwrap_or_else(|| self.name.clone())
+    }
+}
+
+/// Converts mangled symbol names to readable form
+pub trait Demangler: Send + Sync {
+    /// Demangled form of `name` or `None` if it is not mangled in a recognized scheme
+    fn demangle(&self, name: &str) -> Option<String>;
+}
+
+/// Demangles MSVC (`?` prefixed) and Itanium (`_Z` prefixed) names
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DefaultDemangler;
+impl Demangler for DefaultDemangler {
+    fn demangle(&self, name: &str) -> Option<String> {
+        if name.starts_with('?') {
+            msvc_demangler::demangle(name, msvc_demangler::DemangleFlags::llvm()).ok()
+        } else if name.starts_with("_Z") {
+            cpp_demangle::Symbol::new(name)
+                .ok()?
+                .demangle(&Default::default())
+                .ok()
+        } else {
+            None
+        }
     }
 }
 
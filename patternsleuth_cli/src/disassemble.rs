@@ -294,6 +294,52 @@ where
     output.buffer
 }
 
+/// Build a signature covering whole instructions of at least `len` bytes starting at `address`.
+/// Relative branch targets and RIP-relative displacements are wildcarded (`None`) so the
+/// signature survives relinking
+pub(crate) fn signature_at(
+    exe: &Image,
+    address: usize,
+    len: usize,
+) -> anyhow::Result<Vec<Option<u8>>> {
+    let data = exe.memory.range_from(address..)?;
+    let mut decoder = Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE);
+
+    let mut sig = vec![];
+    while sig.len() < len && decoder.can_decode() {
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            anyhow::bail!("invalid instruction at {:#x}", instruction.ip());
+        }
+        let offsets = decoder.get_constant_offsets(&instruction);
+
+        let start = instruction.ip() as usize - address;
+        let mut bytes = data[start..start + instruction.len()]
+            .iter()
+            .map(|b| Some(*b))
+            .collect::<Vec<_>>();
+
+        let mut wildcard = |offset: usize, size: usize| {
+            bytes[offset..offset + size].fill(None);
+        };
+        if instruction.is_ip_rel_memory_operand() && offsets.has_displacement() {
+            wildcard(offsets.displacement_offset(), offsets.displacement_size());
+        }
+        if instruction.op_kinds().any(|op| {
+            matches!(
+                op,
+                OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64
+            )
+        }) && offsets.has_immediate()
+        {
+            wildcard(offsets.immediate_offset(), offsets.immediate_size());
+        }
+
+        sig.extend(bytes);
+    }
+    Ok(sig)
+}
+
 pub(crate) fn get_xrefs(address: usize, data: &[u8]) -> Vec<(usize, usize)> {
     let mut xrefs = vec![];
     for instruction in Decoder::with_ip(64, data, address as u64, DecoderOptions::NONE) {
@@ -31,6 +31,8 @@ enum Commands {
     BuildIndex(CommandBuildIndex),
     ViewSymbol(CommandViewSymbol),
     AutoGen(CommandAutoGen),
+    ListResolvers(CommandListResolvers),
+    Dump(CommandDump),
 }
 
 fn parse_maybe_hex(s: &str) -> Result<usize> {
@@ -197,6 +199,48 @@ struct CommandViewSymbol {
 #[derive(Parser)]
 struct CommandAutoGen {}
 
+/// List every registered resolver and whether it is a singleton. Resolvers do not declare which
+/// engine versions they support so none is reported, run `report` against games of the versions
+/// of interest to see which resolvers succeed
+#[derive(Parser)]
+struct CommandListResolvers {
+    /// Output as JSON instead of one resolver per line
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SignatureFormat {
+    /// Print every format
+    All,
+    /// patternsleuth pattern syntax
+    Native,
+    /// IDA style with single `?` wildcards
+    Ida,
+    /// Ghidra memory search style with `..` wildcards
+    Ghidra,
+    /// Escaped byte string and `x?` mask for use in code
+    Code,
+}
+
+#[derive(Parser)]
+struct CommandDump {
+    /// Path to executable to read from
+    path: PathBuf,
+
+    /// Address to generate a signature at
+    #[arg(short, long, value_parser(parse_maybe_hex))]
+    addr: usize,
+
+    /// Minimum signature length in bytes (extended to cover whole instructions)
+    #[arg(short, long, default_value = "32", value_parser(parse_maybe_hex))]
+    len: usize,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = SignatureFormat::All)]
+    format: SignatureFormat,
+}
+
 fn find_ext<P: AsRef<Path>, E: AsRef<str>>(dir: P, ext: &[E]) -> Result<Option<PathBuf>> {
     for f in fs::read_dir(dir)? {
         let f = f?.path();
@@ -231,7 +275,82 @@ fn main() -> Result<()> {
         Commands::BuildIndex(command) => db::build(command),
         Commands::ViewSymbol(command) => db::view(command),
         Commands::AutoGen(command) => db::auto_gen(command),
+        Commands::ListResolvers(command) => list_resolvers(command),
+        Commands::Dump(command) => dump(command),
+    }
+}
+
+fn dump(command: CommandDump) -> Result<()> {
+    let bin_data = fs::read(&command.path)?;
+    let exe = Image::builder().build(&bin_data)?;
+
+    let sig = disassemble::signature_at(&exe, command.addr, command.len)?;
+    // trailing wildcards do not contribute to matching
+    let sig = &sig[..sig.iter().rposition(Option::is_some).map_or(0, |i| i + 1)];
+
+    let join = |byte: fn(u8) -> String, wildcard: &str, sep: &str| {
+        sig.iter()
+            .map(|b| b.map(byte).unwrap_or_else(|| wildcard.to_string()))
+            .join(sep)
+    };
+    let formats = [
+        (
+            SignatureFormat::Native,
+            join(|b| format!("{b:02X}"), "??", " "),
+        ),
+        (SignatureFormat::Ida, join(|b| format!("{b:02X}"), "?", " ")),
+        (
+            SignatureFormat::Ghidra,
+            join(|b| format!("{b:02x}"), "..", " "),
+        ),
+        (
+            SignatureFormat::Code,
+            format!(
+                "\"{}\" \"{}\"",
+                join(|b| format!("\\x{b:02X}"), "\\x00", ""),
+                sig.iter()
+                    .map(|b| if b.is_some() { 'x' } else { '?' })
+                    .join("")
+            ),
+        ),
+    ];
+    for (format, sig) in formats {
+        if command.format == SignatureFormat::All {
+            println!("{:<8} {sig}", format!("{format:?}:"));
+        } else if command.format == format {
+            println!("{sig}");
+        }
     }
+    Ok(())
+}
+
+fn list_resolvers(command: CommandListResolvers) -> Result<()> {
+    let resolvers = resolvers().sorted_by_key(|r| r.name).collect_vec();
+    if command.json {
+        let list = resolvers
+            .iter()
+            // supported engine versions are not tracked per resolver
+            .map(|r| {
+                serde_json::json!({
+                    "name": r.name,
+                    "singleton": r.singleton,
+                    "engine_versions": null,
+                })
+            })
+            .collect_vec();
+        println!("{}", serde_json::to_string_pretty(&list)?);
+    } else {
+        // on stderr so stdout stays one resolver per line
+        eprintln!("note: supported engine versions are not tracked per resolver");
+        for r in resolvers {
+            println!(
+                "{} {}",
+                r.name,
+                if r.singleton { "singleton" } else { "struct" }
+            );
+        }
+    }
+    Ok(())
 }
 
 // TODO remove, only used for patterns/xrefs from CLI
@@ -468,9 +587,17 @@ fn scan(command: CommandScan) -> Result<()> {
                                     format!("{:016x} {:?}{}", m.1.address, m.0, count)
                                         .normal()
                                         .to_string(),
-                                    exe.symbols
-                                        .as_ref()
-                                        .and_then(|symbols| symbols.get(&m.1.address)),
+                                    exe.nearest_symbol_demangled(
+                                        m.1.address,
+                                        &patternsleuth::symbols::DefaultDemangler,
+                                    )
+                                    .map(|(name, delta)| {
+                                        if delta == 0 {
+                                            name.to_string()
+                                        } else {
+                                            format!("{name}+0x{delta:x}")
+                                        }
+                                    }),
                                 )
                             })
                             .collect::<Vec<_>>();
@@ -480,7 +607,7 @@ fn scan(command: CommandScan) -> Result<()> {
                                 line.push_str(&format!(
                                     "{}{}",
                                     " ".repeat(1 + max_len.unwrap() - line.len()),
-                                    symbol.name.bright_yellow()
+                                    symbol.bright_yellow()
                                 ));
                             }
                         }
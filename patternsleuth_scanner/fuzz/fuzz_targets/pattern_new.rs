@@ -0,0 +1,15 @@
+//! Arbitrary input must never cause `Pattern::new` to panic and any successfully parsed pattern
+//! must round trip through its canonical string form
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use patternsleuth_scanner::Pattern;
+
+fuzz_target!(|input: &str| {
+    if let Ok(pattern) = Pattern::new(input) {
+        let canonical = pattern.to_string();
+        let reparsed = Pattern::new(&canonical)
+            .unwrap_or_else(|e| panic!("failed to reparse {canonical:?}: {e}"));
+        assert_eq!(pattern, reparsed, "round trip through {canonical:?}");
+    }
+});
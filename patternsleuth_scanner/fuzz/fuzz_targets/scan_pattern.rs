@@ -0,0 +1,43 @@
+//! Compare the optimized multi-pattern scanner against a naive scalar scan over random
+//! haystacks
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use patternsleuth_scanner::{scan_pattern, Pattern};
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    patterns: Vec<String>,
+    base_address: u32,
+    haystack: Vec<u8>,
+}
+
+fn scan_scalar(patterns: &[&Pattern], base_address: usize, data: &[u8]) -> Vec<Vec<usize>> {
+    patterns
+        .iter()
+        .map(|p| {
+            (0..(data.len() + 1).saturating_sub(p.simple.len()))
+                .filter(|&i| p.is_match(data, base_address, i))
+                .map(|i| p.compute_result(data, base_address, i))
+                .collect()
+        })
+        .collect()
+}
+
+fuzz_target!(|input: Input| {
+    let patterns = input
+        .patterns
+        .iter()
+        .filter_map(|p| Pattern::new(p).ok())
+        .collect::<Vec<_>>();
+    let patterns = patterns.iter().collect::<Vec<_>>();
+    let base_address = input.base_address as usize;
+
+    let mut expected = scan_scalar(&patterns, base_address, &input.haystack);
+    let mut actual = scan_pattern(&patterns, base_address, &input.haystack);
+    for results in expected.iter_mut().chain(actual.iter_mut()) {
+        results.sort();
+    }
+    assert_eq!(expected, actual);
+});
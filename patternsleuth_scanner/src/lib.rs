@@ -1,10 +1,41 @@
-use anyhow::{bail, Context, Error, Result};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-#[derive(Clone, Eq, PartialEq)]
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use anyhow::{Error, Result};
+
+#[derive(Clone)]
 pub struct PatternSimple {
     pub sig: Vec<u8>,
     pub mask: Vec<u8>,
 }
+/// Compare normalized bytes such that signature bits not covered by the mask are ignored
+impl PartialEq for PatternSimple {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((sig_a, mask_a), (sig_b, mask_b))| {
+                    mask_a == mask_b && sig_a & mask_a == sig_b & mask_b
+                })
+    }
+}
+impl Eq for PatternSimple {}
+impl core::hash::Hash for PatternSimple {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        for (sig, mask) in self.iter() {
+            (sig & mask, mask).hash(state);
+        }
+    }
+}
 impl PatternSimple {
     #[inline(always)]
     pub fn is_match(&self, data: &[u8], index: usize) -> bool {
@@ -19,12 +50,12 @@ impl PatternSimple {
     pub fn len(&self) -> usize {
         self.sig.len()
     }
-    pub fn iter(&self) -> std::iter::Zip<std::slice::Iter<u8>, std::slice::Iter<u8>> {
+    pub fn iter(&self) -> core::iter::Zip<core::slice::Iter<u8>, core::slice::Iter<u8>> {
         self.sig.iter().zip(&self.mask)
     }
 }
 
-fn fmt_byte(f: &mut std::fmt::Formatter<'_>, sig: u8, mask: u8) -> std::fmt::Result {
+fn fmt_byte(f: &mut core::fmt::Formatter<'_>, sig: u8, mask: u8) -> core::fmt::Result {
     if mask == 0 {
         write!(f, "??")?;
     } else if mask == 0xff {
@@ -45,28 +76,82 @@ fn fmt_byte(f: &mut std::fmt::Formatter<'_>, sig: u8, mask: u8) -> std::fmt::Res
     Ok(())
 }
 
+/// Write a word separator unless this is the first word
+fn fmt_separator(f: &mut core::fmt::Formatter<'_>, first: &mut bool) -> core::fmt::Result {
+    if !core::mem::replace(first, false) {
+        write!(f, " ")?;
+    }
+    Ok(())
+}
+
 impl Display for PatternSimple {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:02X}", self.sig[0])?;
-        for (sig, mask) in self.iter().skip(1) {
-            write!(f, " ")?;
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut first = true;
+        for (sig, mask) in self.iter() {
+            fmt_separator(f, &mut first)?;
             fmt_byte(f, *sig, *mask)?;
         }
         Ok(())
     }
 }
-impl std::fmt::Debug for PatternSimple {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Debug for PatternSimple {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "PatternSimple(\"{self}\")")
     }
 }
 
-#[derive(Clone, Eq, PartialEq)]
+/// Words accepted as full byte wildcards by [`Pattern::new`]
+pub const DEFAULT_WILDCARDS: &[&str] = &["??", "?"];
+/// Wildcard spellings used by common signature tools, for [`Pattern::new_with_wildcards`]
+pub const COMMON_WILDCARDS: &[&str] = &["??", "?", "*", "**"];
+
+/// Error produced by [`Pattern::new`] for malformed pattern strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternParseError {
+    /// Character index into `pattern` of the offending word
+    pub position: usize,
+    pub message: String,
+    /// The pattern string that failed to parse
+    pub pattern: String,
+}
+impl Display for PatternParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} at position {} in pattern \"{}\"",
+            self.message, self.position, self.pattern
+        )
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for PatternParseError {}
+
+#[derive(Clone)]
 pub struct Pattern {
     pub simple: PatternSimple,
     pub custom_offset: usize,
-    pub captures: Vec<std::ops::Range<usize>>,
+    pub captures: Vec<core::ops::Range<usize>>,
     pub xrefs: Vec<(usize, Xref)>,
+    /// Informational description (e.g. the C++ signature of the function the pattern locates)
+    /// shown in diagnostics. Ignored when matching and comparing patterns
+    pub label: Option<String>,
+}
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.simple == other.simple
+            && self.custom_offset == other.custom_offset
+            && self.captures == other.captures
+            && self.xrefs == other.xrefs
+    }
+}
+impl Eq for Pattern {}
+impl core::hash::Hash for Pattern {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.simple.hash(state);
+        self.custom_offset.hash(state);
+        self.captures.hash(state);
+        self.xrefs.hash(state);
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -76,13 +161,13 @@ pub struct Capture<'data> {
 }
 
 impl TryFrom<String> for Pattern {
-    type Error = Error;
+    type Error = PatternParseError;
     fn try_from(string: String) -> Result<Self, <Self as TryFrom<String>>::Error> {
         Self::new(string)
     }
 }
 impl TryFrom<&str> for Pattern {
-    type Error = Error;
+    type Error = PatternParseError;
     fn try_from(string: &str) -> Result<Self, <Self as TryFrom<&str>>::Error> {
         Self::new(string)
     }
@@ -135,16 +220,34 @@ impl Pattern {
     fn parse_maybe_hex(s: &str) -> Result<usize> {
         Ok(s.strip_prefix("0x")
             .map(|s| usize::from_str_radix(s, 16))
-            .unwrap_or_else(|| s.parse())?)
+            .unwrap_or_else(|| s.parse())
+            .map_err(Error::msg)?)
     }
 
     fn parse_maybe_hex_u32(s: &str) -> Result<u32> {
         Ok(s.strip_prefix("0x")
             .map(|s| u32::from_str_radix(s, 16))
-            .unwrap_or_else(|| s.parse())?)
+            .unwrap_or_else(|| s.parse())
+            .map_err(Error::msg)?)
+    }
+
+    pub fn new<S: AsRef<str>>(s: S) -> Result<Self, PatternParseError> {
+        Self::new_with_wildcards(s, DEFAULT_WILDCARDS)
     }
+    /// Parse a pattern treating every word in `wildcards` as a full byte wildcard, e.g.
+    /// [`COMMON_WILDCARDS`] to accept signatures copied from tools using `*`. Wildcard words take
+    /// precedence over hex bytes so `&["2A"]` makes `2A` a wildcard rather than `0x2A`
+    pub fn new_with_wildcards<S: AsRef<str>>(
+        s: S,
+        wildcards: &[&str],
+    ) -> Result<Self, PatternParseError> {
+        let s = s.as_ref();
+        let error = |byte: usize, message: String| PatternParseError {
+            position: s[..byte].chars().count(),
+            message,
+            pattern: s.into(),
+        };
 
-    pub fn new<S: AsRef<str>>(s: S) -> Result<Self> {
         let mut sig = vec![];
         let mut mask = vec![];
         let mut custom_offset = 0;
@@ -154,8 +257,14 @@ impl Pattern {
         let mut xrefs = vec![];
 
         let mut i = 0;
-        for w in s.as_ref().split_whitespace() {
-            if let Some((s, m)) =
+        for w in s.split_whitespace() {
+            // words are subslices of `s` so their byte offset can be recovered from the pointer
+            let pos = w.as_ptr() as usize - s.as_ptr() as usize;
+            if wildcards.contains(&w) {
+                sig.push(0);
+                mask.push(0);
+                i += 1;
+            } else if let Some((s, m)) =
                 Self::parse_hex_pattern(w).or_else(|| Self::parse_binary_patern(w))
             {
                 sig.push(s);
@@ -167,19 +276,23 @@ impl Pattern {
                         custom_offset = i;
                     }
                     "[" => {
-                        capture_stack.push(i);
+                        capture_stack.push((i, pos));
                     }
                     "]" => {
-                        if let Some(start) = capture_stack.pop() {
+                        if let Some((start, _)) = capture_stack.pop() {
                             captures.push(start..i);
                         } else {
-                            bail!("unexpected closing capture at word {i}");
+                            return Err(error(
+                                pos,
+                                format!("unexpected closing capture at word {i}"),
+                            ));
                         }
                     }
                     _ => {
                         if let Some(xref) = w.strip_prefix('X').map(Self::parse_maybe_hex) {
-                            let xref =
-                                Xref(xref.with_context(|| format!("failed to parse xref {w}"))?);
+                            let xref = Xref(xref.map_err(|e| {
+                                error(pos, format!("failed to parse xref {w}: {e}"))
+                            })?);
                             xrefs.push((sig.len(), xref));
                             for _ in 0..4 {
                                 sig.push(0);
@@ -187,24 +300,26 @@ impl Pattern {
                             }
                             i += 4;
                         } else if w.starts_with("0x") {
-                            sig.extend(u32::to_le_bytes(
-                                Self::parse_maybe_hex_u32(w)
-                                    .with_context(|| format!("failed to parse 4-bytes hex {w}"))?,
-                            ));
+                            sig.extend(u32::to_le_bytes(Self::parse_maybe_hex_u32(w).map_err(
+                                |e| error(pos, format!("failed to parse 4-bytes hex {w}: {e}")),
+                            )?));
                             mask.extend([0xff; 4]);
                             i += 4;
                         } else {
-                            bail!("bad pattern word \"{}\"", w)
+                            return Err(error(pos, format!("bad pattern word \"{}\"", w)));
                         }
                     }
                 }
             }
         }
-        if let Some(start) = capture_stack.pop() {
-            bail!("unclosed capture at word {start}");
+        if let Some((start, pos)) = capture_stack.pop() {
+            return Err(error(pos, format!("unclosed capture at word {start}")));
         }
         if sig.is_empty() {
-            bail!("pattern must match at least one byte");
+            return Err(error(
+                s.len(),
+                "pattern must match at least one byte".into(),
+            ));
         }
 
         Ok(Self {
@@ -212,8 +327,21 @@ impl Pattern {
             custom_offset,
             captures,
             xrefs,
+            label: None,
         })
     }
+    /// Parse a pattern and attach a descriptive `label`, see [`Pattern::label`]
+    pub fn new_labeled<S: AsRef<str>>(
+        s: S,
+        label: impl Into<String>,
+    ) -> Result<Self, PatternParseError> {
+        Ok(Self::new(s)?.with_label(label))
+    }
+    /// Attach a descriptive `label`, see [`Pattern::label`]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
     /// Create a pattern from a literal `Vec<u8>` with `mask` filled with 0xff and `custom_offset = 0`.
     pub fn from_bytes(sig: Vec<u8>) -> Result<Self> {
         Ok(Self {
@@ -224,8 +352,15 @@ impl Pattern {
             custom_offset: 0,
             captures: vec![],
             xrefs: vec![],
+            label: None,
         })
     }
+    /// Number of bytes which must match exactly, counting xrefs as they are fully determined by
+    /// the target address. Short literals tend to match unrelated code on other builds
+    pub fn literal_len(&self) -> usize {
+        let fixed = self.simple.mask.iter().filter(|m| **m == 0xff).count();
+        fixed + self.xrefs.len() * 4
+    }
     #[inline(always)]
     pub fn is_match(&self, data: &[u8], base_address: usize, index: usize) -> bool {
         self.simple.is_match(data, index)
@@ -254,49 +389,288 @@ impl Pattern {
                 .collect()
         })
     }
+    /// Returns true if there exists some input which both `self` and `other` match such that
+    /// they resolve to the same address (i.e. taking `custom_offset` into account)
+    pub fn can_collide(&self, other: &Pattern) -> bool {
+        // start of `other` relative to start of `self` when results are equal
+        let shift = self.custom_offset as isize - other.custom_offset as isize;
+
+        let bytes_compatible = other.simple.iter().enumerate().all(|(j, (sig_b, mask_b))| {
+            let i = j as isize + shift;
+            if i < 0 || i as usize >= self.simple.len() {
+                return true;
+            }
+            let (sig_a, mask_a) = (self.simple.sig[i as usize], self.simple.mask[i as usize]);
+            let common = mask_a & mask_b;
+            sig_a & common == sig_b & common
+        });
+
+        // xrefs at the same location can only both match if they point to the same address
+        let xrefs_compatible = self.xrefs.iter().all(|(offset_a, xref_a)| {
+            other.xrefs.iter().all(|(offset_b, xref_b)| {
+                *offset_a as isize != *offset_b as isize + shift || xref_a == xref_b
+            })
+        });
+
+        bytes_compatible && xrefs_compatible
+    }
     /// compute virtual address from address relative to section as well as account for
     /// custom_offset
     pub fn compute_result(&self, _data: &[u8], base_address: usize, index: usize) -> usize {
         base_address + index + self.custom_offset
     }
+    /// Compare the pattern byte by byte against `data` starting at `index` to show why it does
+    /// or does not match there. Only signature bytes are compared, xrefs depend on the address
+    /// of the data and are reported as wildcards
+    pub fn explain_at(&self, data: &[u8], index: usize) -> Explanation {
+        let bytes = self
+            .simple
+            .iter()
+            .enumerate()
+            .map(|(i, (sig, mask))| ByteExplanation {
+                sig: *sig,
+                mask: *mask,
+                actual: index.checked_add(i).and_then(|i| data.get(i)).copied(),
+            })
+            .collect::<Vec<_>>();
+        let first_mismatch = bytes.iter().position(|b| !b.is_match());
+        Explanation {
+            bytes,
+            first_mismatch,
+        }
+    }
 }
 
-impl Display for Pattern {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:02X}", self.simple.sig[0])?;
-        let mut iter = self.simple.iter().enumerate().skip(1);
-        while let Some((i, (sig, mask))) = iter.next() {
-            if i == self.custom_offset {
-                write!(f, " |")?;
+/// Result of [`Pattern::explain_at`] for a single pattern byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteExplanation {
+    pub sig: u8,
+    pub mask: u8,
+    /// Byte found in the data or `None` if the pattern extends past the end of the data
+    pub actual: Option<u8>,
+}
+impl ByteExplanation {
+    pub fn is_wildcard(&self) -> bool {
+        self.mask == 0
+    }
+    pub fn is_match(&self) -> bool {
+        self.actual
+            .is_some_and(|actual| actual & self.mask == self.sig)
+    }
+}
+
+/// Per byte comparison of a pattern against data returned by [`Pattern::explain_at`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub bytes: Vec<ByteExplanation>,
+    /// Index of the first pattern byte which did not match
+    pub first_mismatch: Option<usize>,
+}
+impl Explanation {
+    pub fn is_match(&self) -> bool {
+        self.first_mismatch.is_none()
+    }
+}
+/// One line per pattern byte: offset, expected, actual and whether it matched
+impl Display for Explanation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, byte) in self.bytes.iter().enumerate() {
+            write!(f, "{i:4}  ")?;
+            fmt_byte(f, byte.sig, byte.mask)?;
+            match byte.actual {
+                Some(actual) => write!(f, "  {actual:02X}")?,
+                None => write!(f, "  --")?,
             }
-            write!(f, " ")?;
-            if *mask == 0 {
-                if let Some((_offset, xref)) =
-                    self.xrefs.iter().find(|(offset, _xref)| *offset == i)
-                {
-                    write!(f, "X0x{:X}", xref.0)?;
-                    iter.nth(2); // skip 3
-                    continue;
+            if self.first_mismatch == Some(i) {
+                write!(f, "  <- first mismatch")?;
+            } else if !byte.is_match() {
+                write!(f, "  <- mismatch")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Canonical string form of the pattern such that `Pattern::new(p.to_string()) == p` holds for
+/// any pattern produced by [`Pattern::new`]
+impl Display for Pattern {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut first = true;
+        let len = self.simple.len();
+        let mut i = 0;
+        loop {
+            // captures are stored in the order they were closed so emit closing brackets (and
+            // empty captures) in that same order
+            for capture in self.captures.iter().filter(|c| c.end == i) {
+                if capture.is_empty() {
+                    fmt_separator(f, &mut first)?;
+                    write!(f, "[")?;
                 }
+                fmt_separator(f, &mut first)?;
+                write!(f, "]")?;
+            }
+            if i != 0 && i == self.custom_offset {
+                fmt_separator(f, &mut first)?;
+                write!(f, "|")?;
+            }
+            // outer captures must be opened first which means they were closed last
+            for _ in self
+                .captures
+                .iter()
+                .rev()
+                .filter(|c| c.start == i && !c.is_empty())
+            {
+                fmt_separator(f, &mut first)?;
+                write!(f, "[")?;
+            }
+
+            if i >= len {
+                break;
+            }
+
+            fmt_separator(f, &mut first)?;
+            if let Some((_offset, xref)) = self.xrefs.iter().find(|(offset, _xref)| *offset == i) {
+                write!(f, "X0x{:X}", xref.0)?;
+                i += 4;
+            } else {
+                fmt_byte(f, self.simple.sig[i], self.simple.mask[i])?;
+                i += 1;
             }
-            fmt_byte(f, *sig, *mask)?;
         }
         Ok(())
     }
 }
-impl std::fmt::Debug for Pattern {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Pattern(\"{self}\")")
+impl core::fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "Pattern(\"{self}\", label = {label:?})"),
+            None => write!(f, "Pattern(\"{self}\")"),
+        }
     }
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, Ord, PartialEq, PartialOrd)]
 pub struct Xref(pub usize);
 
-use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+/// Patterns serialize as their canonical string form (see the [`Display`] impl) along with the
+/// optional label, so masks, captures, xrefs and custom offset all round-trip exactly
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct SerializedPattern<P> {
+        pattern: P,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    }
+
+    impl Serialize for Pattern {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            SerializedPattern {
+                pattern: self.to_string(),
+                label: self.label.clone(),
+            }
+            .serialize(serializer)
+        }
+    }
+    impl<'de> Deserialize<'de> for Pattern {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let SerializedPattern::<String> { pattern, label } =
+                SerializedPattern::deserialize(deserializer)?;
+            let mut pattern = Pattern::new(pattern).map_err(de::Error::custom)?;
+            pattern.label = label;
+            Ok(pattern)
+        }
+    }
+}
+
+/// Statically find all pairs of patterns (by index) which could match and resolve to the same
+/// address on some input. Useful for catching redundant or overly similar signatures.
+pub fn find_collisions(patterns: &[&Pattern]) -> Vec<(usize, usize)> {
+    let mut collisions = vec![];
+    for (i, a) in patterns.iter().enumerate() {
+        for (j, b) in patterns.iter().enumerate().skip(i + 1) {
+            if a.can_collide(b) {
+                collisions.push((i, j));
+            }
+        }
+    }
+    collisions
+}
+
+/// Find all pairs of patterns (by index) which are identical after normalization. Each
+/// duplicate is paired with the first occurrence of its pattern
+#[cfg(feature = "std")]
+pub fn find_duplicates(patterns: &[&Pattern]) -> Vec<(usize, usize)> {
+    use std::collections::hash_map::Entry;
+
+    let mut first: HashMap<&Pattern, usize> = HashMap::new();
+    let mut duplicates = vec![];
+    for (i, p) in patterns.iter().enumerate() {
+        match first.entry(p) {
+            Entry::Occupied(e) => duplicates.push((*e.get(), i)),
+            Entry::Vacant(e) => {
+                e.insert(i);
+            }
+        }
+    }
+    duplicates
+}
+
+/// Panic if any two patterns are identical. Intended for tests guarding a set of resolver
+/// signatures against unintentional duplicates
+#[cfg(feature = "std")]
+pub fn assert_no_duplicates(patterns: &[&Pattern]) {
+    let duplicates = find_duplicates(patterns);
+    assert!(
+        duplicates.is_empty(),
+        "duplicate patterns: {}",
+        duplicates
+            .iter()
+            .map(|(a, b)| format!("{a} and {b} ({})", patterns[*a]))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+}
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::{
     fmt::Display,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
 };
+// lookup tables on the scan hot path are hashed when `std` is available
+#[cfg(not(feature = "std"))]
+use alloc::collections::{BTreeMap as HashMap, BTreeSet as HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+/// Number of chunks to split scanned data into, i.e. one per thread when `std` is available
+fn chunk_size(len: usize) -> usize {
+    #[cfg(feature = "std")]
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    #[cfg(not(feature = "std"))]
+    let threads = 1;
+    (len / threads).max(1)
+}
+
+/// Iterate chunks in parallel via rayon when `std` is available, otherwise sequentially
+macro_rules! chunk_iter {
+    ($chunks:expr) => {{
+        #[cfg(feature = "std")]
+        let iter = {
+            use rayon::prelude::*;
+            $chunks.par_iter()
+        };
+        #[cfg(not(feature = "std"))]
+        let iter = $chunks.iter();
+        iter
+    }};
+}
 
 #[derive(Debug, Eq, PartialEq)]
 struct PatternPair<'p> {
@@ -313,19 +687,20 @@ impl PatternPair<'_> {
         offset: usize,
         pattern_index: usize,
         matches: &mut Vec<(usize, usize)>,
-    ) {
-        if self.partial.is_match(data, offset)
+    ) -> bool {
+        let found = self.partial.is_match(data, offset)
             && offset >= self.offset
             && self
                 .pattern
-                .is_match(data, base_address, offset - self.offset)
-        {
+                .is_match(data, base_address, offset - self.offset);
+        if found {
             matches.push((
                 pattern_index,
                 self.pattern
                     .compute_result(data, base_address, offset - self.offset),
             ));
         }
+        found
     }
 }
 
@@ -341,13 +716,13 @@ fn group_patterns<'p>(patterns: &[&'p Pattern]) -> Vec<PatternPair<'p>> {
         position_score: usize,
         pattern_indexes: BTreeSet<usize>,
     }
-    impl std::cmp::PartialOrd for ByteSelector {
-        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    impl core::cmp::PartialOrd for ByteSelector {
+        fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
             Some(self.cmp(other))
         }
     }
-    impl std::cmp::Ord for ByteSelector {
-        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    impl core::cmp::Ord for ByteSelector {
+        fn cmp(&self, other: &Self) -> core::cmp::Ordering {
             self.is_banned
                 .cmp(&other.is_banned)
                 .reverse()
@@ -416,8 +791,247 @@ fn group_patterns<'p>(patterns: &[&'p Pattern]) -> Vec<PatternPair<'p>> {
 }
 
 pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) -> Vec<Vec<usize>> {
-    use rayon::prelude::*;
+    scan_pattern_impl(patterns, base_address, data, &BudgetState::unlimited())
+}
+
+/// Like [`scan_pattern`] but only tests addresses that are a multiple of `stride`, e.g. 8 when
+/// searching for pointer tables. Only correct if the caller knows matches are aligned
+pub fn scan_pattern_aligned(
+    patterns: &[&Pattern],
+    base_address: usize,
+    data: &[u8],
+    stride: usize,
+) -> Vec<Vec<usize>> {
+    assert!(stride > 0, "stride must be non-zero");
+    let first = (stride - base_address % stride) % stride;
+    patterns
+        .iter()
+        .map(|p| {
+            let end = (data.len() + 1).saturating_sub(p.simple.len());
+            (first..end)
+                .step_by(stride)
+                .filter(|&i| p.is_match(data, base_address, i))
+                .map(|i| p.compute_result(data, base_address, i))
+                .collect()
+        })
+        .collect()
+}
+
+/// Limits applied to each pattern individually by [`scan_pattern_with_budget`]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ScanBudget {
+    /// Maximum number of candidate offsets (positions where the pattern's anchor bytes match)
+    /// verified per pattern
+    pub max_candidates: Option<usize>,
+    /// Maximum number of matches per pattern, the pattern is abandoned as soon as this many are
+    /// found. Useful to fail fast on signatures which are expected to be unique
+    pub max_matches: Option<usize>,
+    /// Wall-clock deadline after which patterns still producing candidates are abandoned
+    #[cfg(feature = "std")]
+    pub deadline: Option<std::time::Instant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetExceeded {
+    Candidates,
+    Deadline,
+    Matches,
+}
+impl Display for BudgetExceeded {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BudgetExceeded::Candidates => write!(f, "pattern exceeded candidate budget"),
+            BudgetExceeded::Deadline => write!(f, "pattern exceeded scan deadline"),
+            BudgetExceeded::Matches => write!(f, "pattern exceeded match budget"),
+        }
+    }
+}
+#[cfg(feature = "std")]
+impl std::error::Error for BudgetExceeded {}
+
+struct BudgetState {
+    budget: Option<ScanBudget>,
+    candidates: Vec<AtomicUsize>,
+    matches: Vec<AtomicUsize>,
+    /// 0 = within budget, otherwise `BudgetExceeded` discriminant + 1
+    exceeded: Vec<AtomicU8>,
+}
+impl BudgetState {
+    fn unlimited() -> Self {
+        Self {
+            budget: None,
+            candidates: vec![],
+            matches: vec![],
+            exceeded: vec![],
+        }
+    }
+    fn new(budget: ScanBudget, patterns: usize) -> Self {
+        Self {
+            budget: Some(budget),
+            candidates: (0..patterns).map(|_| Default::default()).collect(),
+            matches: (0..patterns).map(|_| Default::default()).collect(),
+            exceeded: (0..patterns).map(|_| Default::default()).collect(),
+        }
+    }
+    /// Account for one candidate of pattern `pi`, returning false if it should be skipped
+    #[inline(always)]
+    fn charge(&self, pi: usize) -> bool {
+        let Some(budget) = &self.budget else {
+            return true;
+        };
+        if self.exceeded[pi].load(Ordering::Relaxed) != 0 {
+            return false;
+        }
+        let n = self.candidates[pi].fetch_add(1, Ordering::Relaxed) + 1;
+        #[cfg(feature = "std")]
+        let past_deadline = || {
+            n % 1024 == 0
+                && budget
+                    .deadline
+                    .is_some_and(|d| std::time::Instant::now() > d)
+        };
+        #[cfg(not(feature = "std"))]
+        let past_deadline = || false;
+        let exceeded = if budget.max_candidates.is_some_and(|max| n > max) {
+            Some(BudgetExceeded::Candidates)
+        } else if past_deadline() {
+            Some(BudgetExceeded::Deadline)
+        } else {
+            None
+        };
+        if let Some(exceeded) = exceeded {
+            self.exceeded[pi].store(exceeded as u8 + 1, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+    /// Account for one match of pattern `pi`, abandoning it once the match limit is reached
+    #[inline(always)]
+    fn found(&self, pi: usize) {
+        let Some(max) = self.budget.as_ref().and_then(|b| b.max_matches) else {
+            return;
+        };
+        if self.matches[pi].fetch_add(1, Ordering::Relaxed) + 1 >= max {
+            let _ = self.exceeded[pi].compare_exchange(
+                0,
+                BudgetExceeded::Matches as u8 + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+        }
+    }
+    fn exceeded(&self, pi: usize) -> Option<BudgetExceeded> {
+        match self.exceeded.get(pi)?.load(Ordering::Relaxed) {
+            1 => Some(BudgetExceeded::Candidates),
+            2 => Some(BudgetExceeded::Deadline),
+            3 => Some(BudgetExceeded::Matches),
+            _ => None,
+        }
+    }
+}
+
+/// Like [`scan_pattern`] but abandons any pattern exceeding `budget`, returning an error for
+/// that pattern while the remaining patterns complete normally
+pub fn scan_pattern_with_budget(
+    patterns: &[&Pattern],
+    base_address: usize,
+    data: &[u8],
+    budget: ScanBudget,
+) -> Vec<Result<Vec<usize>, BudgetExceeded>> {
+    let state = BudgetState::new(budget, patterns.len());
+    scan_pattern_impl(patterns, base_address, data, &state)
+        .into_iter()
+        .enumerate()
+        .map(|(pi, matches)| match state.exceeded(pi) {
+            Some(exceeded) => Err(exceeded),
+            None => Ok(matches),
+        })
+        .collect()
+}
+
+/// Incremental scanner for data read in contiguous pages, e.g. from a live process, so sections
+/// never have to be resident in full. The last `pattern_len - 1` bytes of each page are kept
+/// so matches straddling a page seam are found exactly once
+pub struct StreamScanner<'p> {
+    patterns: Vec<&'p Pattern>,
+    /// Number of trailing bytes retained between pages
+    overlap: usize,
+    window: Vec<u8>,
+    /// Address of `window[0]`
+    window_address: usize,
+    results: Vec<Vec<usize>>,
+}
+
+impl<'p> StreamScanner<'p> {
+    pub fn new(patterns: &[&'p Pattern]) -> Self {
+        Self {
+            patterns: patterns.to_vec(),
+            overlap: patterns
+                .iter()
+                .map(|p| p.simple.len().saturating_sub(1))
+                .max()
+                .unwrap_or_default(),
+            window: vec![],
+            window_address: 0,
+            results: patterns.iter().map(|_| vec![]).collect(),
+        }
+    }
+
+    /// Scan the next page located at `address`. If `address` does not directly follow the
+    /// previously fed page the retained bytes are discarded, no match will span the gap
+    pub fn feed(&mut self, address: usize, data: &[u8]) {
+        if self.window_address.checked_add(self.window.len()) != Some(address) {
+            self.window.clear();
+            self.window_address = address;
+        }
+
+        // matches starting in the retained window and ending in the new page
+        let mut seam = core::mem::take(&mut self.window);
+        let window_len = seam.len();
+        seam.extend_from_slice(&data[..self.overlap.min(data.len())]);
+        for (p, results) in self.patterns.iter().zip(self.results.iter_mut()) {
+            let len = p.simple.len();
+            let start = (window_len + 1).saturating_sub(len);
+            let end = (seam.len() + 1).saturating_sub(len).min(window_len);
+            results.extend(
+                (start..end)
+                    .filter(|&i| p.is_match(&seam, self.window_address, i))
+                    .map(|i| p.compute_result(&seam, self.window_address, i)),
+            );
+        }
 
+        // matches entirely within the new page
+        for (results, page) in
+            self.results
+                .iter_mut()
+                .zip(scan_pattern(&self.patterns, address, data))
+        {
+            results.extend(page);
+        }
+
+        if data.len() >= self.overlap {
+            self.window = data[data.len() - self.overlap..].to_vec();
+            self.window_address = address + data.len() - self.overlap;
+        } else {
+            let drop = seam.len().saturating_sub(self.overlap);
+            self.window_address += drop;
+            seam.drain(..drop);
+            self.window = seam;
+        }
+    }
+
+    /// Return the results of every page fed so far, in the same layout as [`scan_pattern`]
+    pub fn finish(self) -> Vec<Vec<usize>> {
+        self.results
+    }
+}
+
+fn scan_pattern_impl(
+    patterns: &[&Pattern],
+    base_address: usize,
+    data: &[u8],
+    budget: &BudgetState,
+) -> Vec<Vec<usize>> {
     let mut result_bins = patterns.iter().map(|_| vec![]).collect::<Vec<_>>();
 
     if patterns.is_empty() {
@@ -427,7 +1041,21 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
     const WIDE1: usize = 2;
     const WIDE2: usize = 4;
 
-    let pattern_pairs = group_patterns(patterns);
+    // patterns without a single fully masked byte cannot be located via byte search so are
+    // instead tested at every offset
+    let (anchored, unanchored): (Vec<_>, Vec<_>) =
+        (0..patterns.len()).partition(|&i| patterns[i].simple.mask.contains(&0xff));
+    for &pi in &unanchored {
+        let p = patterns[pi];
+        result_bins[pi].extend(
+            (0..(data.len() + 1).saturating_sub(p.simple.len()))
+                .filter(|&i| budget.charge(pi) && p.is_match(data, base_address, i))
+                .inspect(|_| budget.found(pi))
+                .map(|i| p.compute_result(data, base_address, i)),
+        );
+    }
+
+    let pattern_pairs = group_patterns(&anchored.iter().map(|&i| patterns[i]).collect::<Vec<_>>());
 
     let mut all_bins = HashSet::new();
     let mut short_bins: HashMap<u8, Vec<_>> = Default::default();
@@ -459,13 +1087,10 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
     let mut matches = vec![];
 
     // middle
-    let chunk_size = (middle.len()
-        / std::thread::available_parallelism().unwrap_or(std::num::NonZeroUsize::new(1).unwrap()))
-    .max(1);
+    let chunk_size = chunk_size(middle.len());
     let chunks: Vec<_> = middle.chunks(chunk_size).enumerate().collect();
     matches.append(
-        &mut chunks
-            .par_iter()
+        &mut chunk_iter!(chunks)
             .map(|(index, chunk)| {
                 let mut matches = vec![];
                 let offset = index * chunk_size;
@@ -475,7 +1100,11 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
                         let j = offset + i;
                         if let Some(patterns) = short_bins.get(first) {
                             for (pi, p) in patterns.iter() {
-                                p.add_match(data, base_address, j, *pi, &mut matches)
+                                if budget.charge(anchored[*pi])
+                                    && p.add_match(data, base_address, j, *pi, &mut matches)
+                                {
+                                    budget.found(anchored[*pi]);
+                                }
                             }
                         }
                         if !wide2_bins.is_empty() {
@@ -483,7 +1112,11 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
                             buf.copy_from_slice(&data[j..j + WIDE2]);
                             if let Some(patterns) = wide2_bins.get(&buf) {
                                 for (pi, p) in patterns.iter() {
-                                    p.add_match(data, base_address, j, *pi, &mut matches)
+                                    if budget.charge(anchored[*pi])
+                                        && p.add_match(data, base_address, j, *pi, &mut matches)
+                                    {
+                                        budget.found(anchored[*pi]);
+                                    }
                                 }
                             }
                         }
@@ -492,7 +1125,11 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
                             buf.copy_from_slice(&data[j..j + WIDE1]);
                             if let Some(patterns) = wide1_bins.get(&buf) {
                                 for (pi, p) in patterns.iter() {
-                                    p.add_match(data, base_address, j, *pi, &mut matches)
+                                    if budget.charge(anchored[*pi])
+                                        && p.add_match(data, base_address, j, *pi, &mut matches)
+                                    {
+                                        budget.found(anchored[*pi]);
+                                    }
                                 }
                             }
                         }
@@ -510,22 +1147,21 @@ pub fn scan_pattern(patterns: &[&Pattern], base_address: usize, data: &[u8]) ->
         for i in (start.saturating_sub(p.offset))
             ..start + (data.len() - middle.len()).saturating_sub(p.pattern.simple.len() - 1)
         {
-            if p.pattern.is_match(data, base_address, i) {
-                matches.push((pi, base_address + i));
+            if budget.charge(anchored[pi]) && p.pattern.is_match(data, base_address, i) {
+                budget.found(anchored[pi]);
+                matches.push((pi, p.pattern.compute_result(data, base_address, i)));
             }
         }
     }
 
     for (pi, addr) in matches {
-        result_bins[pi].push(addr);
+        result_bins[anchored[pi]].push(addr);
     }
 
     result_bins
 }
 
 pub fn scan_xref(patterns: &[&Xref], base_address: usize, data: &[u8]) -> Vec<Vec<usize>> {
-    use rayon::prelude::*;
-
     let mut bins = patterns.iter().map(|_| vec![]).collect::<Vec<_>>();
 
     if patterns.is_empty() {
@@ -540,14 +1176,11 @@ pub fn scan_xref(patterns: &[&Xref], base_address: usize, data: &[u8]) -> Vec<Ve
     let width = 4;
 
     let first_byte_data = &data[0..data.len().saturating_sub(width - 1)];
-    let chunk_size = (first_byte_data.len()
-        / std::thread::available_parallelism().unwrap_or(std::num::NonZeroUsize::new(1).unwrap()))
-    .max(1);
+    let chunk_size = chunk_size(first_byte_data.len());
 
     let chunks: Vec<_> = first_byte_data.chunks(chunk_size).enumerate().collect();
     matches.append(
-        &mut chunks
-            .par_iter()
+        &mut chunk_iter!(chunks)
             .map(|(chunk_index, chunk)| {
                 let mut matches = vec![];
                 let offset = chunk_index * chunk_size;
@@ -644,6 +1277,7 @@ mod test {
                 custom_offset: 0,
                 captures: vec![],
                 xrefs: vec![],
+                label: None,
             },
             Pattern::new("00 ??").unwrap()
         );
@@ -656,6 +1290,7 @@ mod test {
                 custom_offset: 0,
                 captures: vec![],
                 xrefs: vec![],
+                label: None,
             },
             Pattern::new("10 ??").unwrap()
         );
@@ -668,6 +1303,7 @@ mod test {
                 custom_offset: 0,
                 captures: vec![],
                 xrefs: vec![],
+                label: None,
             },
             Pattern::new("10 ?? 01?10?11").unwrap()
         );
@@ -717,11 +1353,96 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_display_round_trip() {
+        for p in [
+            "12 34 | 56",
+            "?? 34",
+            "X0x1234 56",
+            "12 X0x34",
+            "12 34 |",
+            "0x12345678 ?1 2?",
+            "?0000001 ??100??1",
+            "10 ?? [ ?? ?? ?? ?? ] 20",
+            "00 [ ?? [ ] ] [ 10 20 ]",
+            "[ [ 10 ] 20 [ 30 ] ] [ ] 40",
+            "48 8d 15 [ X0x1000 ] e8 | [ ?? ?? ?? ?? ]",
+        ] {
+            let pattern = Pattern::new(p).unwrap();
+            assert_eq!(
+                pattern,
+                Pattern::new(pattern.to_string()).unwrap(),
+                "{p} -> {pattern}"
+            );
+        }
+        assert_eq!(
+            Pattern::new("00 [ ?? [ ] ] [ 10 20 ]").unwrap().to_string(),
+            "00 [ ?? [ ] ] [ 10 20 ]"
+        );
+        assert_eq!(
+            Pattern::new("48 8b ?? c3").unwrap().to_string(),
+            "48 8B ?? C3"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        for pattern in [
+            Pattern::new("48 8d 15 [ X0x1000 ] e8 | [ ?? ?? ?? ?? ]").unwrap(),
+            Pattern::new("?0000001 ??100??1 [ ] 1?").unwrap(),
+            Pattern::new_labeled("12 34 | 56", "void Foo()").unwrap(),
+        ] {
+            let json = serde_json::to_string(&pattern).unwrap();
+            let de: Pattern = serde_json::from_str(&json).unwrap();
+            assert_eq!(pattern, de, "{json}");
+            assert_eq!(pattern.label, de.label);
+        }
+        assert_eq!(
+            r#"{"pattern":"12 ?? | 34"}"#,
+            serde_json::to_string(&Pattern::new("12 ?? | 34").unwrap()).unwrap()
+        );
+        assert!(serde_json::from_str::<Pattern>(r#"{"pattern":"12 ]"}"#).is_err());
+    }
+
+    #[test]
+    fn test_literal_len() {
+        assert_eq!(2, Pattern::new("48 ?? 8b").unwrap().literal_len());
+        assert_eq!(1, Pattern::new("4? 8b ?8").unwrap().literal_len());
+        assert_eq!(6, Pattern::new("e8 X0x1000 c3").unwrap().literal_len());
+    }
+
+    #[test]
+    fn test_wildcard_aliases() {
+        let expected = Pattern::new("48 ?? 8b ??").unwrap();
+        assert_eq!(expected, Pattern::new("48 ? 8b ??").unwrap());
+        assert!(Pattern::new("48 * 8b").is_err());
+        assert_eq!(
+            expected,
+            Pattern::new_with_wildcards("48 * 8b **", COMMON_WILDCARDS).unwrap()
+        );
+        assert_eq!(
+            expected,
+            Pattern::new_with_wildcards("48 2A 8b 2a", &["2A", "2a"]).unwrap()
+        );
+        // nibble wildcards are unaffected
+        assert_eq!(
+            Pattern::new("4? ?8").unwrap(),
+            Pattern::new_with_wildcards("4? ?8", COMMON_WILDCARDS).unwrap()
+        );
+    }
+
     #[test]
     fn test_captures() {
         assert!(Pattern::new("?? [ ??").is_err());
         assert!(Pattern::new("?? ] ??").is_err());
         assert!(Pattern::new("[ ] ?? ] ??").is_err());
+
+        let err = Pattern::new("?? ??  zz ??").unwrap_err();
+        assert_eq!(7, err.position);
+        assert_eq!("?? ??  zz ??", err.pattern);
+        assert_eq!(3, Pattern::new("?? [ ??").unwrap_err().position);
+        assert_eq!(7, Pattern::new("[ ] ?? ] ??").unwrap_err().position);
         assert_eq!(
             Pattern {
                 simple: PatternSimple {
@@ -731,6 +1452,7 @@ mod test {
                 custom_offset: 0,
                 captures: vec![2..2, 1..2, 2..4],
                 xrefs: vec![],
+                label: None,
             },
             Pattern::new("00 [ ?? [ ] ] [ 10 20 ]").unwrap()
         );
@@ -756,6 +1478,83 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_explain_at() {
+        let p = Pattern::new("48 ?? 1? ff").unwrap();
+
+        let e = p.explain_at(&[0x00, 0x48, 0x12, 0x13, 0xff], 1);
+        assert!(e.is_match());
+        assert!(e.bytes[1].is_wildcard());
+
+        let e = p.explain_at(&[0x48, 0x00, 0x21, 0xfe], 0);
+        assert_eq!(Some(2), e.first_mismatch);
+        assert!(!e.bytes[3].is_match());
+        assert_eq!(
+            "   0  48  48\n   1  ??  00\n   2  1?  21  <- first mismatch\n   3  FF  FE  <- mismatch\n",
+            e.to_string()
+        );
+
+        let e = p.explain_at(&[0x48, 0x00], 0);
+        assert_eq!(Some(2), e.first_mismatch);
+        assert_eq!(None, e.bytes[3].actual);
+    }
+
+    #[test]
+    fn test_find_collisions() {
+        let p = |s| Pattern::new(s).unwrap();
+
+        assert!(p("12 34").can_collide(&p("12 ??")));
+        assert!(p("12 34").can_collide(&p("12 34 56")));
+        assert!(!p("12 34").can_collide(&p("12 35")));
+        assert!(p("12 3?").can_collide(&p("1? 35")));
+        assert!(!p("0??????1").can_collide(&p("???????0")));
+
+        // resolve to the same address via custom offset
+        assert!(p("12 | 34").can_collide(&p("34")));
+        assert!(!p("12 | 34").can_collide(&p("12")));
+        assert!(p("12 34 | 56").can_collide(&p("34 | 56 78")));
+
+        assert!(p("e8 X0x10").can_collide(&p("e8 X0x10")));
+        assert!(!p("e8 X0x10").can_collide(&p("e8 X0x20")));
+        assert!(p("e8 X0x10").can_collide(&p("e8 ?? ?? ?? ??")));
+
+        assert_eq!(
+            find_collisions(&[&p("12 34"), &p("56"), &p("12 ?? 78"), &p("?? 34")]),
+            vec![(0, 2), (0, 3), (1, 3), (2, 3)]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_find_duplicates() {
+        let p = |s| Pattern::new(s).unwrap();
+
+        // bits outside of the mask are ignored
+        let unnormalized = Pattern {
+            simple: PatternSimple {
+                sig: vec![0x12, 0xff],
+                mask: vec![0xff, 0xf0],
+            },
+            ..p("12 f?")
+        };
+        assert_eq!(p("12 f?"), unnormalized);
+        assert_ne!(p("12 f?"), p("12 ??"));
+        assert_ne!(p("12 | 34"), p("12 34"));
+        assert_eq!(p("12 34").with_label("a"), p("12 34"));
+
+        assert_eq!(
+            find_duplicates(&[
+                &p("12 34"),
+                &p("12 ?4"),
+                &p("12 34"),
+                &unnormalized,
+                &p("12 f?")
+            ]),
+            vec![(0, 2), (3, 4)]
+        );
+        assert_no_duplicates(&[&p("12 34"), &p("12 ?4")]);
+    }
+
     #[test]
     fn test_group_patterns() {
         // simple
@@ -893,6 +1692,155 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_scan_unanchored() {
+        // no fully masked byte to search for
+        let patterns = [
+            &Pattern::new("?? 1?").unwrap(),
+            &Pattern::new("12").unwrap(),
+        ];
+        assert_eq!(
+            vec![vec![10, 11], vec![11]],
+            scan_pattern(&patterns, 10, &[0x00, 0x12, 0x13])
+        );
+    }
+
+    #[test]
+    fn test_scan_aligned() {
+        let pattern = Pattern::new("11 22").unwrap();
+        let mut data = vec![0; 32];
+        for i in [3, 8, 13, 24, 30] {
+            data[i] = 0x11;
+            data[i + 1] = 0x22;
+        }
+        assert_eq!(
+            vec![vec![0x1008, 0x1018]],
+            scan_pattern_aligned(&[&pattern], 0x1000, &data, 8)
+        );
+        // alignment is relative to the address rather than the start of the data
+        assert_eq!(
+            vec![vec![0x1008, 0x1018]],
+            scan_pattern_aligned(&[&pattern], 0x1003, &data[3..], 8)
+        );
+        assert_eq!(
+            scan_pattern(&[&pattern], 0x1000, &data),
+            scan_pattern_aligned(&[&pattern], 0x1000, &data, 1)
+        );
+    }
+
+    #[test]
+    fn test_scan_tail() {
+        // matches within the last few bytes of sections of every length must be found by the
+        // tail handling without reading past the end of the section
+        let patterns = [
+            &Pattern::new("aa bb cc").unwrap(),
+            &Pattern::new("bb | cc").unwrap(),
+            &Pattern::new("aa ?? cc dd ee ff").unwrap(),
+            &Pattern::new("cc").unwrap(),
+        ];
+        let tail = [0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff];
+        for len in 0..40 {
+            for cut in 0..=tail.len() {
+                let mut data = vec![0; len];
+                data.extend(&tail[..cut]);
+
+                let expected = patterns
+                    .iter()
+                    .map(|p| {
+                        (0..(data.len() + 1).saturating_sub(p.simple.len()))
+                            .filter(|&i| p.is_match(&data, 0x1000, i))
+                            .map(|i| p.compute_result(&data, 0x1000, i))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+                let mut res = scan_pattern(&patterns, 0x1000, &data);
+                res.iter_mut().for_each(|r| r.sort());
+                assert_eq!(expected, res, "len = {}", data.len());
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_scanner() {
+        let patterns = [
+            &Pattern::new("aa bb cc").unwrap(),
+            &Pattern::new("bb | cc dd").unwrap(),
+            &Pattern::new("aa ?? cc dd ee ff").unwrap(),
+            &Pattern::new("ff").unwrap(),
+        ];
+        let mut data = vec![0; 64];
+        for i in [0, 5, 17, 30, 58] {
+            data[i..i + 6].copy_from_slice(&[0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]);
+        }
+        let mut expected = scan_pattern(&patterns, 0x1000, &data);
+        expected.iter_mut().for_each(|r| r.sort());
+
+        // every page size places seams through every part of every pattern
+        for page in 1..=data.len() {
+            let mut scanner = StreamScanner::new(&patterns);
+            for (i, chunk) in data.chunks(page).enumerate() {
+                scanner.feed(0x1000 + i * page, chunk);
+            }
+            let mut res = scanner.finish();
+            res.iter_mut().for_each(|r| r.sort());
+            assert_eq!(expected, res, "page = {page}");
+        }
+
+        // no match may span a gap between pages
+        let mut scanner = StreamScanner::new(&patterns);
+        scanner.feed(0x1000, &data[..7]);
+        scanner.feed(0x2009, &data[9..]);
+        let mut res = scanner.finish();
+        res.iter_mut().for_each(|r| r.sort());
+        assert_eq!(
+            vec![
+                vec![0x1000, 0x2011, 0x201e, 0x203a],
+                vec![0x1002, 0x2013, 0x2020, 0x203c],
+                vec![0x2011, 0x201e, 0x203a],
+                vec![0x200a, 0x2016, 0x2023, 0x203f],
+            ],
+            res
+        );
+    }
+
+    #[test]
+    fn test_scan_budget() {
+        let patterns = [
+            &Pattern::new("01").unwrap(),
+            &Pattern::new("02 03").unwrap(),
+        ];
+        let data = [[1, 1, 1, 2, 3]; 10].concat();
+
+        let budget = ScanBudget {
+            max_candidates: Some(15),
+            ..Default::default()
+        };
+        let res = scan_pattern_with_budget(&patterns, 0, &data, budget);
+        assert_eq!(res[0], Err(BudgetExceeded::Candidates));
+        assert_eq!(res[1], Ok((0..10).map(|i| i * 5 + 3).collect::<Vec<_>>()));
+
+        let budget = ScanBudget {
+            max_matches: Some(10),
+            ..Default::default()
+        };
+        let res = scan_pattern_with_budget(&patterns, 0, &data, budget);
+        assert_eq!(res[0], Err(BudgetExceeded::Matches));
+        assert_eq!(res[1], Err(BudgetExceeded::Matches));
+        let budget = ScanBudget {
+            max_matches: Some(11),
+            ..Default::default()
+        };
+        let res = scan_pattern_with_budget(&patterns, 0, &data, budget);
+        assert_eq!(res[0], Err(BudgetExceeded::Matches));
+        assert_eq!(res[1], Ok((0..10).map(|i| i * 5 + 3).collect::<Vec<_>>()));
+
+        let res = scan_pattern_with_budget(&patterns, 0, &data, Default::default());
+        assert_eq!(
+            res.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            scan_pattern(&patterns, 0, &data)
+        );
+    }
+
     #[test]
     fn test_scan_xref() {
         test_scan_xref_algo(scan_xref);